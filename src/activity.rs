@@ -0,0 +1,258 @@
+use tcod::colors::*;
+use crate::game::*;
+use crate::game_object::*;
+use crate::map::*;
+use crate::path::first_step;
+
+// Something the player keeps doing turn after turn, until it's done or
+// something interrupts it.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Activity {
+    Rest,
+    Explore,
+    Travel { x: i32, y: i32 },
+    // the direction is the last step taken, it turns to follow corridors
+    Run { dx: i32, dy: i32 }
+}
+
+// an activity in progress, along with how the player was doing on the last
+// turn, so anything that happened since can interrupt it
+#[derive(Clone, Copy, Debug)]
+pub struct Ongoing {
+    activity: Activity,
+    hp: i32,
+    message_count: usize,
+    items_in_view: usize,
+}
+
+pub fn start(activity: Activity, tcod: &Tcod, game: &mut Game, objects: &[GameObject]) {
+    // running just takes the one step then, like walking
+    let is_run = matches!(activity, Activity::Run { .. });
+    if monster_in_view(tcod, objects) {
+        if !is_run {
+            game.messages.add("You can't do that with enemies nearby.", RED);
+        }
+        return;
+    }
+    if is_done(activity, game, objects) {
+        let message = match activity {
+            Activity::Rest => Some("You are already at full health."),
+            Activity::Explore => Some("There's nothing left to explore."),
+            Activity::Travel { .. } => Some("You are already there."),
+            Activity::Run { .. } => None,
+        };
+        if let Some(message) = message {
+            game.messages.add(message, WHITE);
+        }
+        return;
+    }
+    if let Activity::Travel { x, y } = activity {
+        if travel_step(x, y, game, objects).is_none() {
+            game.messages.add("You don't know a way there.", WHITE);
+            return;
+        }
+    }
+    game.activity = Some(Ongoing {
+        activity,
+        hp: 0,
+        message_count: 0,
+        items_in_view: 0,
+    });
+    remember(tcod, game, objects);
+}
+
+pub fn stop(game: &mut Game) {
+    game.activity = None;
+}
+
+// Takes the next turn of the ongoing activity.  Stops it instead when it's
+// done or interrupted.
+pub fn continue_activity(tcod: &mut Tcod, game: &mut Game, objects: &mut Vec<GameObject>) -> PlayerAction {
+    let ongoing = match game.activity {
+        Some(ongoing) => ongoing,
+        None => return PlayerAction::DidntTakeTurn
+    };
+
+    let hp = objects[PLAYER].fighter.map_or(0, |f| f.hp);
+    let interrupted = hp < ongoing.hp
+        || game.messages.total() != ongoing.message_count
+        || monster_in_view(tcod, objects)
+        || items_in_view(tcod, objects) > ongoing.items_in_view;
+    if interrupted {
+        stop(game);
+        return PlayerAction::DidntTakeTurn;
+    }
+
+    if is_done(ongoing.activity, game, objects) {
+        let message = match ongoing.activity {
+            Activity::Rest => Some("You feel rested."),
+            Activity::Explore => Some("You have explored everywhere you can reach."),
+            Activity::Travel { .. } | Activity::Run { .. } => None,
+        };
+        if let Some(message) = message {
+            game.messages.add(message, LIGHT_VIOLET);
+        }
+        stop(game);
+        return PlayerAction::DidntTakeTurn;
+    }
+
+    match ongoing.activity {
+        Activity::Rest => {}
+        Activity::Explore => {
+            if let Some((dx, dy)) = explore_step(game, objects) {
+                move_by(PLAYER, dx, dy, &game.game_map, objects);
+            }
+            if tcod.options.auto_pickup {
+                pick_up_here(game, objects);
+            }
+        }
+        // the way can get blocked along the way
+        Activity::Travel { x, y } => match travel_step(x, y, game, objects) {
+            Some((dx, dy)) => player_move_or_attack(dx, dy, tcod, game, objects),
+            None => {
+                stop(game);
+                return PlayerAction::DidntTakeTurn;
+            }
+        },
+        Activity::Run { dx, dy } => match run_step(dx, dy, game, objects) {
+            Some((dx, dy)) => {
+                player_move_or_attack(dx, dy, tcod, game, objects);
+                if let Some(ongoing) = game.activity.as_mut() {
+                    ongoing.activity = Activity::Run { dx, dy };
+                }
+            }
+            None => {
+                stop(game);
+                return PlayerAction::DidntTakeTurn;
+            }
+        },
+    }
+
+    remember(tcod, game, objects);
+    PlayerAction::TookTurn
+}
+
+fn is_done(activity: Activity, game: &Game, objects: &[GameObject]) -> bool {
+    let player = &objects[PLAYER];
+    match activity {
+        Activity::Rest => player.fighter.is_none_or(|f| f.hp >= player.max_hp(game)),
+        Activity::Explore => explore_step(game, objects).is_none(),
+        Activity::Travel { x, y } => player.pos() == (x, y),
+        // running stops on anything lying on the floor, and on the stairs
+        Activity::Run { .. } => objects.iter().enumerate().any(|(id, o)| {
+            id != PLAYER && o.pos() == player.pos() && (o.item.is_some() || o.name == "stairs")
+        }),
+    }
+}
+
+// the way to the closest tile that hasn't been explored yet
+fn explore_step(game: &Game, objects: &[GameObject]) -> Option<(i32, i32)> {
+    first_step(game, objects, |x, y| !game.game_map[x as usize][y as usize].explored)
+}
+
+fn travel_step(x: i32, y: i32, game: &Game, objects: &[GameObject]) -> Option<(i32, i32)> {
+    first_step(game, objects, |goal_x, goal_y| (goal_x, goal_y) == (x, y))
+}
+
+// Where a run goes next, after the step (dx, dy) brought the player here, or
+// None if it stops.  Along a corridor it follows the bends, and stops where the
+// corridor branches or ends, or opens into a room.  In the open it keeps going
+// straight until it hits something, or passes an opening in the walls to the side.
+fn run_step(dx: i32, dy: i32, game: &Game, objects: &[GameObject]) -> Option<(i32, i32)> {
+    let (x, y) = objects[PLAYER].pos();
+    let previous = (x - dx, y - dy);
+    let in_corridor = is_corridor(x, y, game);
+    // going in or out of a room is a doorway
+    if in_corridor != is_corridor(previous.0, previous.1, game) {
+        return None;
+    }
+
+    let step = if in_corridor && (dx == 0 || dy == 0) {
+        let exits: Vec<_> = ORTHOGONAL
+            .iter()
+            .cloned()
+            .filter(|&(exit_x, exit_y)| (x + exit_x, y + exit_y) != previous)
+            .filter(|&(exit_x, exit_y)| is_open(x + exit_x, y + exit_y, game))
+            .collect();
+        match exits.as_slice() {
+            // stop in the doorway rather than one step into the room
+            &[(exit_x, exit_y)] if is_corridor(x + exit_x, y + exit_y, game) => (exit_x, exit_y),
+            _ => return None,
+        }
+    } else {
+        let sides = |x: i32, y: i32| (is_open(x + dy, y - dx, game), is_open(x - dy, y + dx, game));
+        if !is_open(x + dx, y + dy, game) || sides(x, y) != sides(previous.0, previous.1) {
+            return None;
+        }
+        (dx, dy)
+    };
+
+    // whatever is in the way, like a shopkeeper, stops the run too
+    if is_blocked(x + step.0, y + step.1, &game.game_map, objects) {
+        return None;
+    }
+    Some(step)
+}
+
+const ORTHOGONAL: [(i32, i32); 4] = [(0, -1), (0, 1), (-1, 0), (1, 0)];
+
+fn is_open(x: i32, y: i32, game: &Game) -> bool {
+    x >= 0 && y >= 0 && x < MAP_WIDTH && y < MAP_HEIGHT && !game.game_map[x as usize][y as usize].blocked
+}
+
+// a tile with no more than two ways on, the way in and the way out
+fn is_corridor(x: i32, y: i32, game: &Game) -> bool {
+    ORTHOGONAL
+        .iter()
+        .filter(|&&(dx, dy)| is_open(x + dx, y + dy, game))
+        .count()
+        <= 2
+}
+
+// where the player remembers seeing the stairs, if they've found them
+pub fn known_stairs(game: &Game, objects: &[GameObject]) -> Option<(i32, i32)> {
+    objects
+        .iter()
+        .find(|o| o.name == "stairs" && game.game_map[o.x as usize][o.y as usize].explored)
+        .map(|o| o.pos())
+}
+
+// everything lying under the player, except the remains of monsters
+fn pick_up_here(game: &mut Game, objects: &mut Vec<GameObject>) {
+    while let Some(item_id) = objects.iter().position(|o| {
+        o.pos() == objects[PLAYER].pos() && o.item.is_some_and(|item| item != Item::Corpse)
+    }) {
+        let count = objects.len();
+        pick_item_up(item_id, game, objects);
+        // it stays on the floor if there's no room for it
+        if objects.len() == count {
+            break;
+        }
+    }
+}
+
+fn remember(tcod: &Tcod, game: &mut Game, objects: &[GameObject]) {
+    let hp = objects[PLAYER].fighter.map_or(0, |f| f.hp);
+    let message_count = game.messages.total();
+    let items = items_in_view(tcod, objects);
+    if let Some(ongoing) = game.activity.as_mut() {
+        ongoing.hp = hp;
+        ongoing.message_count = message_count;
+        ongoing.items_in_view = items;
+    }
+}
+
+fn monster_in_view(tcod: &Tcod, objects: &[GameObject]) -> bool {
+    objects.iter().any(|object| {
+        object.ai.is_some() && object.fighter.is_some() && tcod.sight.is_visible(object.x, object.y)
+    })
+}
+
+// the items and stairs in view, so a new one coming into view can stop the player
+fn items_in_view(tcod: &Tcod, objects: &[GameObject]) -> usize {
+    objects
+        .iter()
+        .filter(|object| object.item.is_some() || object.name == "stairs")
+        .filter(|object| tcod.sight.is_visible(object.x, object.y))
+        .count()
+}
@@ -0,0 +1,47 @@
+use crate::map::*;
+use crate::panel::PANEL_Y;
+use crate::game::SCREEN_WIDTH;
+
+// the part of the screen above the panel that the map is shown in
+pub const VIEW_WIDTH: i32 = SCREEN_WIDTH;
+pub const VIEW_HEIGHT: i32 = PANEL_Y;
+
+// Which part of the map is on screen.  The map can be bigger than the screen,
+// so the view scrolls along with the player, but never past the map's edges.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Camera {
+    pub x: i32,
+    pub y: i32,
+}
+
+impl Camera {
+    // puts the tile in the middle of the view
+    pub fn center_on(&mut self, x: i32, y: i32) {
+        self.x = x - VIEW_WIDTH / 2;
+        self.y = y - VIEW_HEIGHT / 2;
+        self.clamp();
+    }
+
+    // scrolls only as far as it takes to get the tile into view
+    pub fn show(&mut self, x: i32, y: i32) {
+        self.x = self.x.clamp(x - VIEW_WIDTH + 1, x);
+        self.y = self.y.clamp(y - VIEW_HEIGHT + 1, y);
+        self.clamp();
+    }
+
+    fn clamp(&mut self) {
+        self.x = self.x.clamp(0, (MAP_WIDTH - VIEW_WIDTH).max(0));
+        self.y = self.y.clamp(0, (MAP_HEIGHT - VIEW_HEIGHT).max(0));
+    }
+
+    // the map tile under a screen position, or None if it's not over the map
+    pub fn to_map(self, screen_x: i32, screen_y: i32) -> Option<(i32, i32)> {
+        let in_view = screen_x >= 0 && screen_y >= 0 && screen_x < VIEW_WIDTH && screen_y < VIEW_HEIGHT;
+        let (x, y) = (screen_x + self.x, screen_y + self.y);
+        if in_view && x < MAP_WIDTH && y < MAP_HEIGHT {
+            Some((x, y))
+        } else {
+            None
+        }
+    }
+}
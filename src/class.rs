@@ -0,0 +1,118 @@
+use serde::{Deserialize, Serialize};
+use tcod::colors::*;
+use crate::equipment::*;
+use crate::game::Game;
+use crate::game_object::*;
+use crate::identification::SCROLLS;
+use crate::map::{new_item, new_torch};
+use crate::spell::{Caster, Spell};
+
+// the chance out of 100 of a rogue landing a critical hit for double damage
+pub const ROGUE_CRITICAL_CHANCE: i32 = 20;
+// the hit points a warrior gets back for every kill
+pub const WARRIOR_SECOND_WIND: i32 = 5;
+
+// Starting items are made as if found above the first level, so they never
+// roll curses or affixes.
+const STARTING_ITEM_LEVEL: u32 = 0;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Class {
+    Warrior,
+    Rogue,
+    Mage
+}
+
+impl Class {
+    pub const ALL: [Class; 3] = [Class::Warrior, Class::Rogue, Class::Mage];
+
+    pub fn description(self) -> &'static str {
+        match self {
+            Class::Warrior => "tough and strong, gets a second wind from every kill",
+            Class::Rogue => "quick and nimble, lands critical hits",
+            Class::Mage => "frail, but knows every scroll and casts lightning",
+        }
+    }
+
+    pub fn fighter(self) -> Fighter {
+        let (hp, defense, power) = match self {
+            Class::Warrior => (120, 2, 3),
+            Class::Rogue => (90, 2, 2),
+            Class::Mage => (70, 0, 1),
+        };
+        Fighter {
+            base_max_hp: hp,
+            hp,
+            base_defense: defense,
+            base_power: power,
+            xp: 0,
+            on_death: DeathCallback::Player,
+        }
+    }
+
+    // every class can learn spells, but only a mage starts out knowing one
+    pub fn caster(self) -> Caster {
+        match self {
+            Class::Warrior => Caster::new(5, vec![]),
+            Class::Rogue => Caster::new(15, vec![]),
+            Class::Mage => Caster::new(30, vec![Spell::Lightning]),
+        }
+    }
+
+    // the items the player starts out with, the weapon and torch already equipped
+    pub fn starting_inventory(self) -> Vec<GameObject> {
+        let mut inventory = match self {
+            Class::Warrior => vec![
+                new_item(Item::Sword, 0, 0, STARTING_ITEM_LEVEL),
+                new_item(Item::Shield, 0, 0, STARTING_ITEM_LEVEL),
+            ],
+            Class::Rogue => {
+                let mut confuse = new_item(Item::Confuse, 0, 0, STARTING_ITEM_LEVEL);
+                confuse.count = 2;
+                vec![new_dagger(), confuse]
+            }
+            Class::Mage => {
+                let mut heal = new_item(Item::Heal, 0, 0, STARTING_ITEM_LEVEL);
+                heal.count = 2;
+                vec![
+                    new_dagger(),
+                    new_item(Item::WandLightning, 0, 0, STARTING_ITEM_LEVEL),
+                    heal,
+                ]
+            }
+        };
+        inventory.push(new_torch(0, 0));
+        for item in inventory.iter_mut() {
+            if let Some(equipment) = item.equipment.as_mut() {
+                equipment.equipped = true;
+            }
+        }
+        inventory
+    }
+
+    // class abilities that take effect when a new game starts
+    pub fn start(self, game: &mut Game) {
+        if self == Class::Mage {
+            for &item in SCROLLS {
+                game.identification.identify(item);
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for Class {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match *self {
+            Class::Warrior => write!(f, "Warrior"),
+            Class::Rogue => write!(f, "Rogue"),
+            Class::Mage => write!(f, "Mage"),
+        }
+    }
+}
+
+fn new_dagger() -> GameObject {
+    let mut dagger = GameObject::new(0, 0, '-', "dagger", SKY, false);
+    dagger.item = Some(Item::Sword);
+    dagger.equipment = Some(Equipment::new(Slot::LeftHand, 2, 0, 0));
+    dagger
+}
@@ -0,0 +1,81 @@
+use tcod::colors::*;
+use crate::game::Game;
+use crate::game_object::GameObject;
+
+const BASE_CAPACITY: i32 = 40;
+
+// nothing can be picked up past this multiple of the carrying capacity
+const MAX_LOAD_FACTOR: i32 = 2;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Encumbrance {
+    Unencumbered,
+    Burdened,
+    Strained
+}
+
+impl Encumbrance {
+    pub fn from_weight(weight: i32, capacity: i32) -> Self {
+        if weight <= capacity {
+            Encumbrance::Unencumbered
+        } else if weight * 2 <= capacity * 3 {
+            Encumbrance::Burdened
+        } else {
+            Encumbrance::Strained
+        }
+    }
+
+    pub fn defense_penalty(self) -> i32 {
+        match self {
+            Encumbrance::Unencumbered => 0,
+            Encumbrance::Burdened => 1,
+            Encumbrance::Strained => 3,
+        }
+    }
+
+    // A slowed down player gives the monsters an extra turn every this many turns
+    pub fn slowdown(self) -> Option<u32> {
+        match self {
+            Encumbrance::Unencumbered => None,
+            Encumbrance::Burdened => Some(4),
+            Encumbrance::Strained => Some(2),
+        }
+    }
+
+    pub fn color(self) -> Color {
+        match self {
+            Encumbrance::Unencumbered => WHITE,
+            Encumbrance::Burdened => YELLOW,
+            Encumbrance::Strained => ORANGE,
+        }
+    }
+}
+
+impl std::fmt::Display for Encumbrance {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match *self {
+            Encumbrance::Unencumbered => write!(f, "Unencumbered"),
+            Encumbrance::Burdened => write!(f, "Burdened"),
+            Encumbrance::Strained => write!(f, "Strained"),
+        }
+    }
+}
+
+// how much the player can carry before slowing down, stronger and tougher players carry more
+pub fn carrying_capacity(player: &GameObject) -> i32 {
+    player
+        .fighter
+        .map_or(0, |f| BASE_CAPACITY + f.base_power * 10 + f.base_max_hp / 5)
+}
+
+pub fn carried_weight(game: &Game) -> i32 {
+    game.inventory.iter().map(|item| item.weight()).sum()
+}
+
+pub fn encumbrance(game: &Game, player: &GameObject) -> Encumbrance {
+    Encumbrance::from_weight(carried_weight(game), carrying_capacity(player))
+}
+
+pub fn can_carry(item: &GameObject, game: &Game, player: &GameObject) -> bool {
+    carried_weight(game) + item.weight() <= carrying_capacity(player) * MAX_LOAD_FACTOR
+}
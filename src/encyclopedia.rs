@@ -0,0 +1,144 @@
+use serde::{Deserialize, Serialize};
+use tcod::colors::*;
+use tcod::console::*;
+use crate::game::*;
+use crate::game_object::*;
+use crate::light::Sight;
+use crate::map::{base_item, new_monster};
+
+const NAME_COLUMN: i32 = 4;
+const STATS_COLUMN: i32 = 32;
+
+// the kinds of monster and item the player has come across
+#[derive(Serialize, Deserialize)]
+pub struct Encyclopedia {
+    monsters: Vec<Monster>,
+    items: Vec<Item>,
+}
+
+impl Encyclopedia {
+    pub fn new() -> Self {
+        Encyclopedia {
+            monsters: vec![],
+            items: vec![],
+        }
+    }
+
+    // notes down everything in view and everything being carried
+    pub fn record(&mut self, sight: &Sight, objects: &[GameObject], inventory: &[GameObject]) {
+        let in_view = objects.iter().filter(|o| sight.is_visible(o.x, o.y));
+        for object in in_view.chain(inventory) {
+            if let Some(monster) = object.monster {
+                if !self.monsters.contains(&monster) {
+                    self.monsters.push(monster);
+                }
+            }
+            if let Some(item) = object.item {
+                if !self.items.contains(&item) {
+                    self.items.push(item);
+                }
+            }
+        }
+    }
+}
+
+// Shows every kind of monster and item seen so far, with what the player
+// knows about it.  The entries come from the same definitions the dungeon is
+// filled from, so they never disagree with what's out there.
+pub fn encyclopedia_screen(tcod: &mut Tcod, game: &Game) {
+    let root = &mut tcod.root;
+    root.set_default_background(BLACK);
+    root.clear();
+    root.set_default_foreground(WHITE);
+    root.print_ex(SCREEN_WIDTH / 2, 0, BackgroundFlag::None, TextAlignment::Center, "Encyclopedia");
+
+    let mut y = 2;
+    heading(root, y, "Monsters");
+    y += 1;
+    for &monster in Monster::ALL.iter().filter(|m| game.encyclopedia.monsters.contains(m)) {
+        let object = new_monster(monster, 0, 0);
+        entry(root, y, &object, &object.name, monster_stats(&object));
+        y += 1;
+    }
+
+    y += 1;
+    heading(root, y, "Items");
+    y += 1;
+    // remains are whatever monster they were, so they aren't listed on their own
+    let items = Item::ALL
+        .iter()
+        .filter(|&&item| item != Item::Corpse && game.encyclopedia.items.contains(&item));
+    for &item in items {
+        let object = base_item(item, 0, 0, game.dungeon_level);
+        let stats = if game.identification.is_identified(item) {
+            item_stats(&object)
+        } else {
+            "unidentified".to_string()
+        };
+        entry(root, y, &object, &object.known_name(game), stats);
+        y += 1;
+    }
+
+    root.set_default_foreground(LIGHT_GREY);
+    root.print_ex(
+        1,
+        SCREEN_HEIGHT - 1,
+        BackgroundFlag::None,
+        TextAlignment::Left,
+        "Press any key to continue",
+    );
+    root.flush();
+    root.wait_for_keypress(true);
+}
+
+fn heading(root: &mut Root, y: i32, text: &str) {
+    root.set_default_foreground(YELLOW);
+    root.print_ex(1, y, BackgroundFlag::None, TextAlignment::Left, text);
+}
+
+// the glyph in its own colour, then the name and the stats
+fn entry(root: &mut Root, y: i32, object: &GameObject, name: &str, stats: String) {
+    root.set_default_foreground(object.color);
+    root.put_char(2, y, object.glyph, BackgroundFlag::None);
+    root.set_default_foreground(WHITE);
+    root.print_ex(NAME_COLUMN, y, BackgroundFlag::None, TextAlignment::Left, name);
+    root.set_default_foreground(LIGHT_GREY);
+    root.print_ex(STATS_COLUMN, y, BackgroundFlag::None, TextAlignment::Left, stats);
+}
+
+fn monster_stats(monster: &GameObject) -> String {
+    monster.fighter.map_or(String::new(), |fighter| {
+        format!(
+            "HP {}, attack {}, defense {}, {} XP",
+            fighter.base_max_hp, fighter.base_power, fighter.base_defense, fighter.xp
+        )
+    })
+}
+
+// e.g. "head, +1 defense, weight 8, 30 gold"
+fn item_stats(item: &GameObject) -> String {
+    let mut stats = vec![];
+    if let Some(equipment) = item.equipment {
+        stats.push(equipment.slot.to_string());
+        if equipment.power_bonus != 0 {
+            stats.push(format!("{:+} attack", equipment.power_bonus));
+        }
+        if equipment.defense_bonus != 0 {
+            stats.push(format!("{:+} defense", equipment.defense_bonus));
+        }
+        if equipment.max_hp_bonus != 0 {
+            stats.push(format!("{:+} HP", equipment.max_hp_bonus));
+        }
+        if equipment.light_radius != 0 {
+            stats.push(format!("light {}", equipment.light_radius));
+        }
+        if equipment.two_handed {
+            stats.push("two-handed".to_string());
+        }
+    }
+    if let Some(kind) = item.item {
+        stats.push(format!("weight {}", kind.weight()));
+        stats.push(format!("{} gold", kind.price()));
+    }
+    stats.join(", ")
+}
@@ -1,20 +1,82 @@
+use rand::distributions::{IndependentSample, Weighted, WeightedChoice};
+use rand::Rng;
 use serde::{Deserialize, Serialize};
+use tcod::colors::*;
 use crate::game_object::GameObject;
+use crate::transition::*;
 
 #[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
 pub struct Equipment {
     pub slot: Slot,
     pub equipped: bool,
+    pub two_handed: bool,
     pub max_hp_bonus: i32,
     pub power_bonus: i32,
     pub defense_bonus: i32,
+    pub prefix: Option<Prefix>,
+    pub suffix: Option<Suffix>,
+    pub rarity: Rarity,
+    pub cursed: bool,
+    pub curse_known: bool,
+    pub light_radius: i32,
+    pub fuel: Option<i32>,
+}
+
+impl Equipment {
+    pub fn new(slot: Slot, power_bonus: i32, defense_bonus: i32, max_hp_bonus: i32) -> Self {
+        Equipment {
+            slot,
+            equipped: false,
+            two_handed: false,
+            max_hp_bonus,
+            power_bonus,
+            defense_bonus,
+            prefix: None,
+            suffix: None,
+            rarity: Rarity::Common,
+            cursed: false,
+            curse_known: false,
+            light_radius: 0,
+            fuel: None,
+        }
+    }
+
+    // two-handed weapons take up both hands
+    pub fn occupies(&self, slot: Slot) -> bool {
+        self.slot == slot || (self.two_handed && slot.is_hand())
+    }
+
+    // where the item is worn, for messages and menus
+    pub fn location(&self) -> String {
+        if self.two_handed {
+            "both hands".into()
+        } else {
+            self.slot.to_string()
+        }
+    }
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
 pub enum Slot {
     LeftHand,
     RightHand,
-    Head
+    Head,
+    Body,
+    Feet,
+    LeftRing,
+    RightRing,
+    Amulet,
+    Light
+}
+
+impl Slot {
+    pub fn is_hand(self) -> bool {
+        self == Slot::LeftHand || self == Slot::RightHand
+    }
+
+    pub fn is_ring(self) -> bool {
+        self == Slot::LeftRing || self == Slot::RightRing
+    }
 }
 
 impl std::fmt::Display for Slot {
@@ -23,15 +85,230 @@ impl std::fmt::Display for Slot {
             Slot::LeftHand => write!(f, "left hand"),
             Slot::RightHand => write!(f, "right hand"),
             Slot::Head => write!(f, "head"),
+            Slot::Body => write!(f, "body"),
+            Slot::Feet => write!(f, "feet"),
+            Slot::LeftRing => write!(f, "left ring finger"),
+            Slot::RightRing => write!(f, "right ring finger"),
+            Slot::Amulet => write!(f, "neck"),
+            Slot::Light => write!(f, "belt"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Rarity {
+    Common,
+    Magic,
+    Rare
+}
+
+impl Rarity {
+    // the colour the item is shown in menus
+    pub fn color(self) -> Color {
+        match self {
+            Rarity::Common => WHITE,
+            Rarity::Magic => LIGHT_BLUE,
+            Rarity::Rare => YELLOW,
+        }
+    }
+
+    pub fn price_factor(self) -> i32 {
+        match self {
+            Rarity::Common => 1,
+            Rarity::Magic => 2,
+            Rarity::Rare => 3,
         }
     }
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Prefix {
+    Sharp,
+    Flaming,
+    Sturdy,
+    Hearty
+}
+
+impl std::fmt::Display for Prefix {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match *self {
+            Prefix::Sharp => write!(f, "sharp"),
+            Prefix::Flaming => write!(f, "flaming"),
+            Prefix::Sturdy => write!(f, "sturdy"),
+            Prefix::Hearty => write!(f, "hearty"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Suffix {
+    Bear,
+    Might,
+    Warding,
+    Titan
+}
+
+impl std::fmt::Display for Suffix {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match *self {
+            Suffix::Bear => write!(f, "of the bear"),
+            Suffix::Might => write!(f, "of might"),
+            Suffix::Warding => write!(f, "of warding"),
+            Suffix::Titan => write!(f, "of the titan"),
+        }
+    }
+}
+
+// Some of the equipment found in the dungeon is cursed.  A cursed item has its
+// bonuses turned into penalties and can't be taken off once it's equipped.
+pub fn roll_curse(object: &mut GameObject, level: u32) {
+    let curse_chance = from_dungeon_level(
+        &[
+            Transition { level: 2, value: 10 },
+            Transition { level: 5, value: 15 },
+            Transition { level: 8, value: 20 },
+        ],
+        level
+    );
+    if let Some(equipment) = object.equipment.as_mut() {
+        if rand::thread_rng().gen_range(0, 100) < curse_chance {
+            equipment.cursed = true;
+            equipment.max_hp_bonus = -equipment.max_hp_bonus;
+            equipment.power_bonus = -equipment.power_bonus;
+            equipment.defense_bonus = -equipment.defense_bonus;
+        }
+    }
+}
+
+// Gives freshly spawned equipment a chance at a prefix and a suffix.  Deeper
+// levels roll affixes more often, unlock the better ones and make them stronger.
+pub fn roll_affixes(object: &mut GameObject, level: u32) {
+    let equipment = match object.equipment.as_mut() {
+        Some(equipment) if !equipment.cursed => equipment,
+        _ => return
+    };
+
+    let prefix_chance = from_dungeon_level(
+        &[
+            Transition { level: 1, value: 10 },
+            Transition { level: 3, value: 25 },
+            Transition { level: 6, value: 40 },
+        ],
+        level
+    );
+    let suffix_chance = from_dungeon_level(
+        &[
+            Transition { level: 2, value: 10 },
+            Transition { level: 4, value: 25 },
+            Transition { level: 7, value: 40 },
+        ],
+        level
+    );
+    let magnitude = from_dungeon_level(
+        &[
+            Transition { level: 1, value: 1 },
+            Transition { level: 5, value: 2 },
+            Transition { level: 9, value: 3 },
+        ],
+        level
+    ) as i32;
+
+    if rand::thread_rng().gen_range(0, 100) < prefix_chance {
+        let prefix_chances = &mut [
+            Weighted { weight: 30, item: Prefix::Sharp },
+            Weighted {
+                weight: from_dungeon_level(&[Transition { level: 4, value: 20 }], level),
+                item: Prefix::Flaming,
+            },
+            Weighted { weight: 30, item: Prefix::Sturdy },
+            Weighted { weight: 20, item: Prefix::Hearty },
+        ];
+        let prefix = WeightedChoice::new(prefix_chances).ind_sample(&mut rand::thread_rng());
+        match prefix {
+            Prefix::Sharp => equipment.power_bonus += magnitude,
+            Prefix::Flaming => equipment.power_bonus += magnitude + 1,
+            Prefix::Sturdy => equipment.defense_bonus += magnitude,
+            Prefix::Hearty => equipment.max_hp_bonus += 10 * magnitude,
+        }
+        equipment.prefix = Some(prefix);
+    }
+
+    if rand::thread_rng().gen_range(0, 100) < suffix_chance {
+        let suffix_chances = &mut [
+            Weighted { weight: 30, item: Suffix::Bear },
+            Weighted { weight: 30, item: Suffix::Might },
+            Weighted {
+                weight: from_dungeon_level(&[Transition { level: 3, value: 30 }], level),
+                item: Suffix::Warding,
+            },
+            Weighted {
+                weight: from_dungeon_level(&[Transition { level: 8, value: 10 }], level),
+                item: Suffix::Titan,
+            },
+        ];
+        let suffix = WeightedChoice::new(suffix_chances).ind_sample(&mut rand::thread_rng());
+        match suffix {
+            Suffix::Bear => equipment.max_hp_bonus += 10 * magnitude,
+            Suffix::Might => equipment.power_bonus += magnitude,
+            Suffix::Warding => equipment.defense_bonus += magnitude,
+            Suffix::Titan => {
+                equipment.power_bonus += magnitude;
+                equipment.defense_bonus += magnitude;
+            }
+        }
+        equipment.suffix = Some(suffix);
+    }
+
+    equipment.rarity = match (equipment.prefix, equipment.suffix) {
+        (None, None) => Rarity::Common,
+        (Some(_), Some(_)) => Rarity::Rare,
+        _ => Rarity::Magic,
+    };
+
+    // "flaming sword of the bear"
+    if let Some(prefix) = equipment.prefix {
+        object.name = format!("{} {}", prefix, object.name);
+    }
+    if let Some(suffix) = equipment.suffix {
+        object.name = format!("{} {}", object.name, suffix);
+    }
+}
+
 pub fn get_equipped_in_slot(slot: Slot, inventory: &[GameObject]) -> Option<usize> {
     for (inventory_id, item) in inventory.iter().enumerate() {
-        if item.equipment.as_ref().map_or(false, |e| e.equipped && e.slot == slot) {
+        if item.equipment.as_ref().map_or(false, |e| e.equipped && e.occupies(slot)) {
             return Some(inventory_id);
         }
     }
     None
 }
+
+// the slot an item should be worn in, a ring goes on whichever hand is free
+pub fn slot_for(equipment: &Equipment, inventory: &[GameObject]) -> Slot {
+    if equipment.slot.is_ring() {
+        for &slot in &[Slot::LeftRing, Slot::RightRing] {
+            if get_equipped_in_slot(slot, inventory).is_none() {
+                return slot;
+            }
+        }
+    }
+    equipment.slot
+}
+
+// the equipped items that have to come off before this equipment can be worn
+pub fn get_conflicting_equipment(equipment: &Equipment, inventory: &[GameObject]) -> Vec<usize> {
+    let slots = if equipment.two_handed {
+        vec![Slot::LeftHand, Slot::RightHand]
+    } else {
+        vec![equipment.slot]
+    };
+    let mut conflicts = vec![];
+    for slot in slots {
+        if let Some(inventory_id) = get_equipped_in_slot(slot, inventory) {
+            if !conflicts.contains(&inventory_id) {
+                conflicts.push(inventory_id);
+            }
+        }
+    }
+    conflicts
+}
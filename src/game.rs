@@ -12,6 +12,23 @@ use crate::map::*;
 use crate::panel::*;
 use crate::game_object::*;
 use crate::menu::*;
+use crate::identification::Identification;
+use crate::hunger::{self, START_NUTRITION};
+use crate::encumbrance::{encumbrance, Encumbrance};
+use crate::light::{self, light_radius, Sight};
+use crate::class::Class;
+use crate::spell;
+use crate::perk::{self, Perks};
+use crate::progression::Progression;
+use crate::activity::{self, Activity, Ongoing};
+use crate::message_log::message_log_screen;
+use crate::targeting::{describe, draw_cursor, look, Cursor};
+use crate::keymap::{Action, Keymap};
+use crate::options::Options;
+use crate::camera::{Camera, VIEW_HEIGHT, VIEW_WIDTH};
+use crate::help::help_screen;
+use crate::overview::{self, overview_screen};
+use crate::encyclopedia::{encyclopedia_screen, Encyclopedia};
 
 // window size
 pub const SCREEN_WIDTH: i32 = 80;
@@ -20,12 +37,25 @@ pub const SCREEN_HEIGHT: i32 = 50;
 // 20 frames per second maximum
 pub const LIMIT_FPS: i32 = 20;
 
+// how much darker the messages from earlier turns are shown
+const OLD_MESSAGE_DIMMING: f32 = 0.4;
+
+const PROGRESSION_ERROR_WIDTH: i32 = 50;
+
 #[derive(Serialize, Deserialize)]
 pub struct Game {
     pub game_map: Map,
     pub messages: Messages,
     pub inventory: Vec<GameObject>,
     pub dungeon_level: u32,
+    pub identification: Identification,
+    pub gold: i32,
+    pub turn: u32,
+    pub progression: Progression,
+    pub encyclopedia: Encyclopedia,
+    // what the player is busy doing, not saved since loading interrupts it anyway
+    #[serde(skip)]
+    pub activity: Option<Ongoing>,
 }
 
 pub struct Tcod {
@@ -33,45 +63,47 @@ pub struct Tcod {
     pub con: Offscreen,
     pub panel: Offscreen,
     pub fov: FovMap,
+    pub sight: Sight,
+    pub cursor: Option<Cursor>,
+    pub camera: Camera,
+    pub keymap: Keymap,
+    pub options: Options,
     pub key: Key,
     pub mouse: Mouse,
 }
 
-pub fn new_game(tcod: &mut Tcod) -> (Game, Vec<GameObject>) {
-    use crate::equipment::*;
-
+pub fn new_game(tcod: &mut Tcod, class: Class) -> (Game, Vec<GameObject>) {
     // create player object
     let mut player = GameObject::new(0, 0, '@', "player", WHITE, true);
     player.alive = true;
-    player.fighter = Some(Fighter {
-        base_max_hp: 100,
-        hp: 100,
-        base_defense: 1,
-        base_power: 2,
-        xp: 0,
-        on_death: DeathCallback::Player,
-    });
+    player.fighter = Some(class.fighter());
+    player.class = Some(class);
+    player.caster = Some(class.caster());
+    player.perks = Some(Perks::new());
+    player.nutrition = Some(START_NUTRITION);
 
     let mut objects = vec![player];
 
+    // a broken progression file shouldn't stop the game from starting
+    let progression = Progression::load().unwrap_or_else(|error| {
+        let text = format!("\n{}\n\nUsing the built-in level-up rules instead.\n", error);
+        msgbox(&text, PROGRESSION_ERROR_WIDTH, &mut tcod.root);
+        Progression::default()
+    });
+
     let mut game = Game {
         game_map: make_map(&mut objects, 1),
         messages: Messages::new(),
-        inventory: vec![],
-        dungeon_level: 1
+        inventory: class.starting_inventory(),
+        dungeon_level: 1,
+        identification: Identification::new(),
+        gold: 0,
+        turn: 0,
+        progression,
+        encyclopedia: Encyclopedia::new(),
+        activity: None,
     };
-
-    // initial equipment: a dagger
-    let mut dagger = GameObject::new(0, 0, '-', "dagger", SKY, false);
-    dagger.item = Some(Item::Sword);
-    dagger.equipment = Some(Equipment {
-        equipped: true,
-        slot: Slot::LeftHand,
-        max_hp_bonus: 0,
-        defense_bonus: 0,
-        power_bonus: 2,
-    });
-    game.inventory.push(dagger);
+    class.start(&mut game);
 
     initialize_fov(tcod, &game.game_map);
 
@@ -118,19 +150,27 @@ pub fn initialize_fov(tcod: &mut Tcod, map: &Map) {
 pub fn play_game(tcod: &mut Tcod, game: &mut Game, objects: &mut Vec<GameObject>) {
     // force FOV "recompute" first time through the game loop
     let mut previous_player_position = (-1, -1);
+    let mut previous_light_radius = -1;
 
     while !tcod.root.window_closed() {
         // clear the screen of the previous frame
         tcod.con.clear();
 
+        // a click only counts on the frame it happens
+        tcod.mouse.lbutton_pressed = false;
         match check_for_event(MOUSE | KEY_PRESS) {
             Some((_, Event::Mouse(m))) => tcod.mouse = m,
             Some((_, Event::Key(k))) => tcod.key = k,
             _ => tcod.key = Default::default(),
         }
 
-        let fov_recompute = previous_player_position != (objects[PLAYER].pos());
+        // the view changes when the player moves or their light changes
+        let fov_recompute = previous_player_position != (objects[PLAYER].pos())
+            || previous_light_radius != light_radius(game);
+        previous_light_radius = light_radius(game);
         render_all(tcod, game, &objects, fov_recompute);
+        game.encyclopedia.record(&tcod.sight, objects, &game.inventory);
+        overview::remember_monsters(&tcod.sight, objects);
 
         tcod.root.flush();
 
@@ -138,18 +178,45 @@ pub fn play_game(tcod: &mut Tcod, game: &mut Game, objects: &mut Vec<GameObject>
         level_up(tcod, game, objects);
 
         // handle keys and exit game if needed
+        // an ongoing activity takes the player's turns until a key is pressed
+        // whatever happens from here on is tagged with the turn being played
         previous_player_position = objects[PLAYER].pos();
-        let player_action = handle_keys(tcod, game, objects);
+        game.messages.set_turn(game.turn + 1);
+        let player_action = if game.activity.is_some() && tcod.key.code == KeyCode::NoKey {
+            activity::continue_activity(tcod, game, objects)
+        } else {
+            activity::stop(game);
+            handle_keys(tcod, game, objects)
+        };
         if player_action == PlayerAction::Exit {
             save_game(game, objects).unwrap();
             break;
         }
 
+        // the player gets hungrier with every turn, and may pass out
+        // a heavily loaded player is slower, so the monsters get extra turns
+        let mut monster_turns = 1;
+        if objects[PLAYER].alive && player_action != PlayerAction::DidntTakeTurn {
+            game.turn += 1;
+            monster_turns += hunger::digest(&mut objects[PLAYER], game);
+            light::burn_light(game);
+            spell::regenerate_mana(&mut objects[PLAYER], game.turn);
+            perk::tick(&mut objects[PLAYER], game);
+            objects[PLAYER].regenerate(game);
+            if let Some(every) = encumbrance(game, &objects[PLAYER]).slowdown() {
+                if game.turn.is_multiple_of(every) {
+                    monster_turns += 1;
+                }
+            }
+        }
+
         // let monsters take their turn
         if objects[PLAYER].alive && player_action != PlayerAction::DidntTakeTurn {
-            for id in 0..objects.len() {
-                if objects[id].ai.is_some() {
-                    ai_take_turn(id, tcod, game, objects);
+            for _ in 0..monster_turns {
+                for id in 0..objects.len() {
+                    if objects[id].ai.is_some() {
+                        ai_take_turn(id, tcod, game, objects);
+                    }
                 }
             }
         }
@@ -157,63 +224,48 @@ pub fn play_game(tcod: &mut Tcod, game: &mut Game, objects: &mut Vec<GameObject>
 }
 
 fn handle_keys(tcod: &mut Tcod, game: &mut Game, objects: &mut Vec<GameObject>) -> PlayerAction {
-    use tcod::input::KeyCode::*;
+    use Action::*;
     use PlayerAction::*;
     let player_alive = objects[PLAYER].alive;
 
-    match (tcod.key, tcod.key.text(), player_alive) {
-        (
-            Key {
-                code: Enter,
-                alt: true,
-                ..
-            },
-            _,
-            _,
-        ) => {
+    // clicking an explored tile walks the player there
+    if tcod.mouse.lbutton_pressed && player_alive {
+        if let Some((x, y)) = tcod.camera.to_map(tcod.mouse.cx as i32, tcod.mouse.cy as i32) {
+            if game.game_map[x as usize][y as usize].explored {
+                activity::start(Activity::Travel { x, y }, tcod, game, objects);
+            }
+        }
+        return DidntTakeTurn;
+    }
+
+    match (tcod.keymap.action(tcod.key), player_alive) {
+        (Some(ToggleFullscreen), _) => {
             let fullscreen = tcod.root.is_fullscreen();
             tcod.root.set_fullscreen(!fullscreen);
             DidntTakeTurn
         }
-        (Key { code: Escape, .. }, _, _) => Exit,
+        (Some(SaveAndQuit), _) => Exit,
         // movement keys
-        (Key { code: Up, .. }, _, true) | (Key { code: NumPad8, .. }, _, true) => {
-            player_move_or_attack(0, -1, game, objects);
-            TookTurn
-        }
-        (Key { code: Down, .. }, _, true) | (Key { code: NumPad2, .. }, _, true) => {
-            player_move_or_attack(0, 1, game, objects);
+        (Some(action), true) if action.direction().is_some() => {
+            let (dx, dy) = action.direction().unwrap();
+            player_move_or_attack(dx, dy, tcod, game, objects);
             TookTurn
         }
-        (Key { code: Left, .. }, _, true) | (Key { code: NumPad4, .. }, _, true) => {
-            player_move_or_attack(-1, 0, game, objects);
-            TookTurn
-        }
-        (Key { code: Right, .. }, _, true) | (Key { code: NumPad6, .. }, _, true) => {
-            player_move_or_attack(1, 0, game, objects);
-            TookTurn
-        }
-        (Key { code: Home, .. }, _, true) | (Key { code: NumPad7, .. }, _, true) => {
-            player_move_or_attack(-1, -1, game, objects);
-            TookTurn
-        }
-        (Key { code: PageUp, .. }, _, true) | (Key { code: NumPad9, .. }, _, true) => {
-            player_move_or_attack(1, -1, game, objects);
-            TookTurn
-        }
-        (Key { code: End, .. }, _, true) | (Key { code: NumPad1, .. }, _, true) => {
-            player_move_or_attack(-1, 1, game, objects);
-            TookTurn
-        }
-        (Key { code: PageDown, .. }, _, true) | (Key { code: NumPad3, .. }, _, true) => {
-            player_move_or_attack(1, 1, game, objects);
+        // run until something interesting happens, after the first step
+        (Some(action), true) if action.run_direction().is_some() => {
+            let (dx, dy) = action.run_direction().unwrap();
+            let position = objects[PLAYER].pos();
+            player_move_or_attack(dx, dy, tcod, game, objects);
+            if objects[PLAYER].pos() != position {
+                activity::start(Activity::Run { dx, dy }, tcod, game, objects);
+            }
             TookTurn
         }
-        (Key { code: NumPad5, .. }, _, true) => {
+        (Some(Wait), true) => {
             TookTurn // do nothing, i.e. wait for the monster to come to you
         }
         // "get" - pick up item
-        (Key { code: Text, .. }, "g", true) => {
+        (Some(PickUp), true) => {
             let item_id = objects
                 .iter()
                 .position(|o| o.pos() == objects[PLAYER].pos() && o.item.is_some());
@@ -223,18 +275,27 @@ fn handle_keys(tcod: &mut Tcod, game: &mut Game, objects: &mut Vec<GameObject>)
             DidntTakeTurn
         }
         // "drop" - drop item
-        (Key { code: Text, .. }, "d", true) => {
+        (Some(Drop), true) => {
             //show the inventory; if an item is selected, drop it
-            let inventory_index = inventory_menu(&game.inventory, "", &mut tcod.root);
+            let inventory_index = inventory_menu(game, &objects[PLAYER], "", &mut tcod.root);
             if let Some(inventory_index) = inventory_index {
-                drop_item(inventory_index, game, objects);
+                // ask how many to drop when the item is a stack
+                let amount = if game.inventory[inventory_index].count > 1 {
+                    drop_amount_menu(&game.inventory[inventory_index], game, &mut tcod.root)
+                } else {
+                    Some(1)
+                };
+                if let Some(amount) = amount {
+                    drop_item(inventory_index, amount, game, objects);
+                }
             }
             DidntTakeTurn
         }
         // view inventory
-        (Key { code: Text, .. }, "i", true) => {
+        (Some(Inventory), true) => {
             let inventory_index = inventory_menu(
-                &game.inventory,
+                game,
+                &objects[PLAYER],
                 "Press the key next to an item to use it, or any other to cancel.\n",
                 &mut tcod.root,
             );
@@ -244,7 +305,7 @@ fn handle_keys(tcod: &mut Tcod, game: &mut Game, objects: &mut Vec<GameObject>)
             DidntTakeTurn
         }
         // take stairs
-        (Key {code: Text, ..}, "<", true) => {
+        (Some(TakeStairs), true) => {
             let player_on_stairs = objects
                 .iter()
                 .any(|object| 
@@ -255,9 +316,77 @@ fn handle_keys(tcod: &mut Tcod, game: &mut Game, objects: &mut Vec<GameObject>)
             }
             DidntTakeTurn
         }
+        // view equipped items
+        (Some(Equipment), true) => {
+            equipment_msgbox(&mut tcod.root, game, &objects[PLAYER]);
+            DidntTakeTurn
+        }
+        // "zap" - cast a spell
+        (Some(Cast), true) => {
+            if spell::cast_menu(tcod, game, objects) {
+                TookTurn
+            } else {
+                DidntTakeTurn
+            }
+        }
+        // rest until healed
+        (Some(Rest), true) => {
+            activity::start(Activity::Rest, tcod, game, objects);
+            DidntTakeTurn
+        }
+        // walk to the stairs, if they've been found
+        (Some(TravelStairs), true) => {
+            match activity::known_stairs(game, objects) {
+                Some((x, y)) => activity::start(Activity::Travel { x, y }, tcod, game, objects),
+                None => game.messages.add("You haven't found the stairs yet.", WHITE),
+            }
+            DidntTakeTurn
+        }
+        // walk to the closest unexplored part of the level, and on until it's all explored
+        (Some(Explore), true) => {
+            activity::start(Activity::Explore, tcod, game, objects);
+            DidntTakeTurn
+        }
+        // use an active ability
+        (Some(Ability), true) => {
+            if perk::ability_menu(tcod, game, objects) {
+                TookTurn
+            } else {
+                DidntTakeTurn
+            }
+        }
+        // view and spend perks
+        (Some(Perks), true) => {
+            perk::perk_screen(tcod, game, objects);
+            DidntTakeTurn
+        }
+        // look around with the cursor
+        (Some(Examine), true) => {
+            look(tcod, game, objects);
+            DidntTakeTurn
+        }
+        // reread old messages
+        (Some(Messages), true) => {
+            message_log_screen(tcod, game);
+            DidntTakeTurn
+        }
+        // see the whole level at once
+        (Some(Overview), true) => {
+            overview_screen(tcod, game, objects);
+            DidntTakeTurn
+        }
+        // list the commands, or what's been seen in the dungeon
+        (Some(Help), _) => {
+            help_screen(tcod);
+            DidntTakeTurn
+        }
+        (Some(Encyclopedia), _) => {
+            encyclopedia_screen(tcod, game);
+            DidntTakeTurn
+        }
         // view character information
-        (Key {code: Text, ..}, "c", true) => {
-            character_information_msgbox(&mut tcod.root, &game, &objects[PLAYER], LEVEL_UP_BASE, LEVEL_UP_FACTOR);
+        (Some(Character), true) => {
+            character_information_msgbox(&mut tcod.root, &game, &objects[PLAYER]);
             DidntTakeTurn
         }
         _ => DidntTakeTurn,
@@ -282,17 +411,23 @@ fn next_level(tcod: &mut Tcod, game: &mut Game, objects: &mut Vec<GameObject>) {
     initialize_fov(tcod, &game.game_map);
 }
 
+// Line of sight is unlimited, but what the player actually sees depends on
+// the lit rooms and on the reach of their own light.
+pub fn compute_fov(tcod: &mut Tcod, game: &Game, objects: &[GameObject]) {
+    let player = &objects[PLAYER];
+    tcod.fov.compute_fov(player.x, player.y, 0, FOV_LIGHT_WALLS, FOV_ALGO);
+    tcod.sight.compute(&tcod.fov, &game.game_map, player, light_radius(game));
+}
+
 pub fn render_all(tcod: &mut Tcod, game: &mut Game, objects: &[GameObject], fov_recompute: bool) {
     if fov_recompute {
         // recompute FOV if needed (the player moved or something)
-        let player = &objects[PLAYER];
-        tcod.fov
-            .compute_fov(player.x, player.y, TORCH_RADIUS, FOV_LIGHT_WALLS, FOV_ALGO);
+        compute_fov(tcod, game, objects);
     }
 
     for y in 0..MAP_HEIGHT {
         for x in 0..MAP_WIDTH {
-            let visable = tcod.fov.is_in_fov(x, y);
+            let visable = tcod.sight.is_visible(x, y);
             let wall = game.game_map[x as usize][y as usize].block_sight;
             let color = match (visable, wall) {
                 (false, true) => COLOR_DARK_WALL,
@@ -315,7 +450,7 @@ pub fn render_all(tcod: &mut Tcod, game: &mut Game, objects: &[GameObject], fov_
     let mut to_draw: Vec<_> = objects
         .iter()
         .filter(|o| {
-            tcod.fov.is_in_fov(o.x, o.y)
+            tcod.sight.is_visible(o.x, o.y)
                 || (o.always_visible && game.game_map[o.x as usize][o.y as usize].explored)
         })
         .collect();
@@ -324,14 +459,23 @@ pub fn render_all(tcod: &mut Tcod, game: &mut Game, objects: &[GameObject], fov_
         object.draw(&mut tcod.con);
     }
 
+    // the look and targeting reticle, if there is one
+    draw_cursor(tcod, objects);
+
+    // the view follows the player, or the cursor while looking around
+    match tcod.cursor {
+        Some(cursor) => tcod.camera.show(cursor.x, cursor.y),
+        None => tcod.camera.center_on(objects[PLAYER].x, objects[PLAYER].y),
+    }
+
     blit(
-        &tcod.con,               // The offscreen console
-        (0, 0),                  // Starting coordinates
-        (MAP_WIDTH, MAP_HEIGHT), // size to blit
-        &mut tcod.root,          // blit destination
-        (0, 0),                  // Coordinates to blit to
-        1.0,                     // Forground opaque
-        1.0,                     // Background opaque
+        &tcod.con,                          // The offscreen console
+        (tcod.camera.x, tcod.camera.y),     // Starting coordinates
+        (VIEW_WIDTH, VIEW_HEIGHT),          // size to blit
+        &mut tcod.root,                     // blit destination
+        (0, 0),                             // Coordinates to blit to
+        1.0,                                // Forground opaque
+        1.0,                                // Background opaque
     );
 
     // prepare to render the GUI panel
@@ -340,15 +484,23 @@ pub fn render_all(tcod: &mut Tcod, game: &mut Game, objects: &[GameObject], fov_
 
     // print game messages, one line at a time
     let mut y = MSG_HEIGHT as i32;
-    for &(ref msg, color) in game.messages.iter().rev() {
-        let msg_height = tcod.panel.get_height_rect(MSG_X, y, MSG_WIDTH, 0, msg);
+    // the messages from earlier turns are dimmed
+    for message in game.messages.iter().rev() {
+        let msg = message.display();
+        let msg_height = tcod.panel.get_height_rect(MSG_X, y, MSG_WIDTH, 0, &msg);
         y -= msg_height;
         if y < 0 {
             break;
         }
-        tcod.panel.set_default_background(color);
-        tcod.panel.print_rect(MSG_X, y, MSG_WIDTH, 0, msg);
+        let color = if message.turn < game.turn {
+            lerp(message.color, BLACK, OLD_MESSAGE_DIMMING)
+        } else {
+            message.color
+        };
+        tcod.panel.set_default_foreground(color);
+        tcod.panel.print_rect(MSG_X, y, MSG_WIDTH, 0, &msg);
     }
+    tcod.panel.set_default_foreground(WHITE);
 
     // show player's stats
     let hp = objects[PLAYER].fighter.map_or(0, |f| f.hp);
@@ -365,14 +517,60 @@ pub fn render_all(tcod: &mut Tcod, game: &mut Game, objects: &[GameObject], fov_
         DARKER_RED,
     );
 
+    // and the mana bar below it
+    if let Some(caster) = objects[PLAYER].caster.as_ref().filter(|c| c.max_mana > 0) {
+        render_bar(
+            &mut tcod.panel,
+            1,
+            2,
+            BAR_WIDTH,
+            "MP",
+            caster.mana,
+            caster.max_mana,
+            LIGHT_BLUE,
+            DARKER_BLUE,
+        );
+    }
+
+    // show how hungry the player is, below the bars
+    if let Some(hunger) = objects[PLAYER].hunger() {
+        tcod.panel.set_default_foreground(hunger.color());
+        tcod.panel.print_ex(
+            1, 3,
+            BackgroundFlag::None,
+            TextAlignment::Left,
+            hunger.to_string()
+        );
+        tcod.panel.set_default_foreground(WHITE);
+    }
+    let load = encumbrance(game, &objects[PLAYER]);
+    if load != Encumbrance::Unencumbered {
+        tcod.panel.set_default_foreground(load.color());
+        tcod.panel.print_ex(
+            BAR_WIDTH + 1, 3,
+            BackgroundFlag::None,
+            TextAlignment::Right,
+            load.to_string()
+        );
+        tcod.panel.set_default_foreground(WHITE);
+    }
+
     // display dungeon level
     tcod.panel.print_ex(
-        1, 3, // (pos x,y)
+        1, 4, // (pos x,y)
         BackgroundFlag::None, 
         TextAlignment::Left,
         format!("Dungeon level: {}", game.dungeon_level)
     );
 
+    // display the gold in the player's purse
+    tcod.panel.print_ex(
+        1, 5,
+        BackgroundFlag::None,
+        TextAlignment::Left,
+        format!("Gold: {}", game.gold)
+    );
+
     // display names of objects under the mouse
     tcod.panel.set_default_background(LIGHT_GREY);
     tcod.panel.print_ex(
@@ -380,7 +578,10 @@ pub fn render_all(tcod: &mut Tcod, game: &mut Game, objects: &[GameObject], fov_
         0,
         BackgroundFlag::None,
         TextAlignment::Left,
-        get_names_under_mouse(tcod.mouse, objects, &tcod.fov),
+        match tcod.cursor {
+            Some(cursor) => describe(cursor.x, cursor.y, game, objects, tcod),
+            None => get_names_under_mouse(tcod.mouse, tcod.camera, game, objects, &tcod.sight),
+        },
     );
 
     // blit the contents of `panel` to the root console
@@ -415,7 +616,8 @@ fn ai_take_turn(
 }
 
 fn ai_basic(monster_id: usize, tcod: &Tcod, game: &mut Game, objects: &mut [GameObject]) -> Ai {
-    // a basic monster takes its turn.  If you can see it, it can see you
+    // a basic monster takes its turn.  If it's in your line of sight, you're
+    // in its, even when it's too dark for you to see it
     let (monster_x, monster_y) = objects[monster_id].pos();
     if tcod.fov.is_in_fov(monster_x, monster_y) {
         if objects[monster_id].distance_to(&objects[PLAYER]) > 2.0 {
@@ -460,12 +662,21 @@ fn ai_confused(
     }
 }
 
-fn get_names_under_mouse(mouse: Mouse, objects: &[GameObject], fov_map: &FovMap) -> String {
-    let (x, y) = (mouse.cx as i32, mouse.cy as i32);
+fn get_names_under_mouse(
+    mouse: Mouse,
+    camera: Camera,
+    game: &Game,
+    objects: &[GameObject],
+    sight: &Sight
+) -> String {
+    let (x, y) = match camera.to_map(mouse.cx as i32, mouse.cy as i32) {
+        Some(pos) => pos,
+        None => return String::new(),
+    };
     let names = objects
         .iter()
-        .filter(|obj| obj.pos() == (x, y) && fov_map.is_in_fov(obj.x, obj.y))
-        .map(|obj| obj.name.clone())
+        .filter(|obj| obj.pos() == (x, y) && sight.is_visible(obj.x, obj.y))
+        .map(|obj| obj.display_name(game))
         .collect::<Vec<_>>();
 
     names.join(", ")
@@ -1,12 +1,20 @@
 use std::cmp;
+use rand::Rng;
 use serde::{Deserialize, Serialize};
 use tcod::colors::*;
 use tcod::console::*;
 use crate::game::*;
 use crate::map::*;
 use crate::panel::Messages;
-use crate::menu::level_up_menu;
+use crate::menu::inventory_menu;
+use crate::class::*;
+use crate::spell::*;
+use crate::perk::*;
+use crate::targeting::target_tile;
 use crate::equipment::*;
+use crate::hunger::*;
+use crate::shop::*;
+use crate::encumbrance::*;
 
 pub const PLAYER: usize = 0;
 
@@ -37,6 +45,17 @@ pub enum Ai {
     }
 }
 
+// the kinds of monster that roam the dungeon
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Monster {
+    Orc,
+    Troll
+}
+
+impl Monster {
+    pub const ALL: [Monster; 2] = [Monster::Orc, Monster::Troll];
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
 pub enum Item {
     Heal,
@@ -44,21 +63,131 @@ pub enum Item {
     Confuse,
     Fireball,
     Sword,
-    Shield
+    Shield,
+    WandLightning,
+    WandConfuse,
+    WandDigging,
+    Recharge,
+    Helmet,
+    Armor,
+    Boots,
+    Ring,
+    Amulet,
+    Greatsword,
+    RemoveCurse,
+    Identify,
+    Ration,
+    Corpse,
+    Gold,
+    Torch,
+    Lantern,
+    SpellbookLightning,
+    SpellbookConfuse,
+    SpellbookFireball
+}
+
+impl Item {
+    pub const ALL: [Item; 26] = [
+        Item::Heal,
+        Item::Lightning,
+        Item::Confuse,
+        Item::Fireball,
+        Item::Sword,
+        Item::Shield,
+        Item::WandLightning,
+        Item::WandConfuse,
+        Item::WandDigging,
+        Item::Recharge,
+        Item::Helmet,
+        Item::Armor,
+        Item::Boots,
+        Item::Ring,
+        Item::Amulet,
+        Item::Greatsword,
+        Item::RemoveCurse,
+        Item::Identify,
+        Item::Ration,
+        Item::Corpse,
+        Item::Gold,
+        Item::Torch,
+        Item::Lantern,
+        Item::SpellbookLightning,
+        Item::SpellbookConfuse,
+        Item::SpellbookFireball,
+    ];
+
+    // what a shopkeeper asks for one of these
+    pub fn price(self) -> i32 {
+        use Item::*;
+        match self {
+            Heal => 20,
+            Lightning => 40,
+            Confuse => 30,
+            Fireball => 60,
+            Sword => 50,
+            Shield => 40,
+            WandLightning => 120,
+            WandConfuse => 90,
+            WandDigging => 100,
+            Recharge => 80,
+            Helmet => 30,
+            Armor => 90,
+            Boots => 25,
+            Ring => 150,
+            Amulet => 150,
+            Greatsword => 110,
+            RemoveCurse => 60,
+            Identify => 25,
+            Ration => 10,
+            Corpse => 1,
+            Gold => 1,
+            Torch => 5,
+            Lantern => 60,
+            SpellbookLightning => 200,
+            SpellbookConfuse => 150,
+            SpellbookFireball => 300
+        }
+    }
+
+    // how heavy one of these is
+    pub fn weight(self) -> i32 {
+        use Item::*;
+        match self {
+            Heal => 2,
+            Lightning | Confuse | Fireball | Recharge | RemoveCurse | Identify => 1,
+            Sword => 10,
+            Shield => 15,
+            WandLightning | WandConfuse | WandDigging => 3,
+            Helmet => 8,
+            Armor => 40,
+            Boots => 6,
+            Ring | Amulet => 1,
+            Greatsword => 25,
+            Ration => 5,
+            Corpse => 30,
+            Gold => 0,
+            Torch => 3,
+            Lantern => 8,
+            SpellbookLightning | SpellbookConfuse | SpellbookFireball => 5
+        }
+    }
 }
 
 const HEAL_AMOUNT: i32 = 40;
+const BASE_REGEN_TURNS: i32 = 20;
+const MIN_REGEN_TURNS: i32 = 5;
 const LIGHTNING_DAMAGE: i32 = 40;
 const LIGHTNING_RANGE: i32 = 5;
 const CONFUSE_NUM_TURNS: i32 = 10;
 const CONFUSE_RANGE: i32 = 8;
 const FIREBALL_RADIUS: i32 = 3;
 const FIREBALL_DAMAGE: i32 = 25;
+const DIG_RANGE: i32 = 8;
+const RECHARGE_MIN: i32 = 2;
+const RECHARGE_MAX: i32 = 4;
 
-pub const LEVEL_UP_BASE: i32 = 200;
-pub const LEVEL_UP_FACTOR: i32 = 150;
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct GameObject {
     pub x: i32,
     pub y: i32,
@@ -70,9 +199,19 @@ pub struct GameObject {
     pub fighter: Option<Fighter>,
     pub ai: Option<Ai>,
     pub item: Option<Item>,
+    pub monster: Option<Monster>,
     pub equipment: Option<Equipment>,
     pub always_visible: bool,
     pub level: i32,
+    pub count: i32,
+    pub charges: Option<i32>,
+    pub nutrition: Option<i32>,
+    pub shop: Option<Shop>,
+    pub class: Option<Class>,
+    pub caster: Option<Caster>,
+    pub perks: Option<Perks>,
+    // where the player last saw a monster, for the overview
+    pub last_seen: Option<(i32, i32)>,
 }
 
 impl GameObject {
@@ -88,12 +227,83 @@ impl GameObject {
             fighter: None,
             ai: None,
             item: None,
+            monster: None,
             equipment: None,
             always_visible: false,
             level: 1,
+            count: 1,
+            charges: None,
+            nutrition: None,
+            shop: None,
+            class: None,
+            caster: None,
+            perks: None,
+            last_seen: None,
+        }
+    }
+
+    // the true name, or the item's random appearance if its kind hasn't been identified yet
+    pub fn known_name(&self, game: &Game) -> String {
+        match (self.item, self.equipment) {
+            (Some(item), _) if !game.identification.is_identified(item) => {
+                game.identification.appearance(item).unwrap().into()
+            }
+            (_, Some(equipment)) if equipment.cursed && equipment.curse_known => {
+                format!("cursed {}", self.name)
+            }
+            _ => self.name.clone(),
         }
     }
 
+    // the name shown to the player, e.g. "3 healing potions" for a stack of items
+    // or "wand of digging (2 charges)" for a charged item, and the fuel left in a light
+    pub fn display_name(&self, game: &Game) -> String {
+        if self.count > 1 {
+            format!("{} {}", self.count, pluralize(&self.known_name(game)))
+        } else if let Some(charges) = self.charges {
+            let plural = if charges == 1 { "" } else { "s" };
+            format!("{} ({} charge{})", self.known_name(game), charges, plural)
+        } else if let Some(fuel) = self.equipment.and_then(|e| e.fuel) {
+            format!("{} ({} turns of light)", self.known_name(game), fuel)
+        } else {
+            self.known_name(game)
+        }
+    }
+
+    // the name with an article or amount, e.g. "a healing potion" or "3 healing potions"
+    pub fn amount_name(&self, game: &Game) -> String {
+        if self.count > 1 {
+            self.display_name(game)
+        } else {
+            format!("a {}", self.known_name(game))
+        }
+    }
+
+    // the price of one of this item, equipment with affixes is worth more
+    pub fn price(&self) -> i32 {
+        let price = self.item.map_or(0, |item| item.price());
+        match self.equipment {
+            Some(equipment) => price * equipment.rarity.price_factor(),
+            None => price
+        }
+    }
+
+    // the weight of the item, or of the whole stack
+    pub fn weight(&self) -> i32 {
+        self.item.map_or(0, |item| item.weight()) * self.count
+    }
+
+    // consumable items of the same kind are kept together in one inventory slot
+    pub fn stacks_with(&self, other: &GameObject) -> bool {
+        self.item.is_some()
+            && self.item == other.item
+            && self.equipment.is_none()
+            && other.equipment.is_none()
+            && self.charges.is_none()
+            && other.charges.is_none()
+            && self.name == other.name
+    }
+
     // set the color and then draw the character that represents this object at its position
     pub fn draw(&self, con: &mut dyn Console) {
         con.set_default_foreground(self.color);
@@ -139,11 +349,18 @@ impl GameObject {
 
     pub fn attack(&mut self, target: &mut GameObject, game: &mut Game) {
         // a simple formulat for attack damage
-        let damage = self.power(game) - target.defense(game);
+        let mut damage = self.power(game) - target.defense(game);
+        if damage > 0 && rand::thread_rng().gen_range(0, 100) < self.critical_chance() {
+            damage *= if self.has_perk(Perk::Assassin) { 3 } else { 2 };
+            game.messages.add(format!("{} lands a critical hit!", self.name), LIGHT_YELLOW);
+        }
         if damage > 0 {
             game.messages.add(format!("{} attacks {} for {} hit points.", self.name, target.name, damage), WHITE);
             if let Some(xp) = target.take_damage(damage, game) {
                 self.fighter.as_mut().unwrap().xp += xp;
+                if self.class == Some(Class::Warrior) {
+                    self.heal(WARRIOR_SECOND_WIND, game);
+                }
             }
         } else {
             game.messages.add(format!("{} attacks {} but it has no effect!", self.name, target.name), WHITE);
@@ -173,9 +390,16 @@ impl GameObject {
             if !equipment.equipped {
                 equipment.equipped = true;
                 messages.add(
-                    format!("Equipped {} on {}.", self.name, equipment.slot),
+                    format!("Equipped {} on {}.", self.name, equipment.location()),
                     LIGHT_GREEN
                 );  
+                if equipment.cursed && !equipment.curse_known {
+                    equipment.curse_known = true;
+                    messages.add(
+                        format!("The {} is cursed! It binds itself to you.", self.name),
+                        DARK_RED
+                    );
+                }
             }
         } else {
             messages.add(
@@ -185,19 +409,28 @@ impl GameObject {
         };
     }
 
-    pub fn dequip(&mut self, messages: &mut Messages) {
+    // returns false if the item couldn't be taken off
+    pub fn dequip(&mut self, messages: &mut Messages) -> bool {
         if self.item.is_none() {
             messages.add(
                 format!("Can't dequip {:?} because it's not an Item.", self),
                 RED
             );
-            return;
+            return false;
         };
         if let Some(ref mut equipment) = self.equipment {
-            if !equipment.equipped {
+            if equipment.equipped && equipment.cursed {
+                equipment.curse_known = true;
+                messages.add(
+                    format!("You can't remove the {}, it's cursed!", self.name),
+                    DARK_RED
+                );
+                return false;
+            }
+            if equipment.equipped {
                 equipment.equipped = false;
                 messages.add(
-                    format!("Dequipped {} on {}.", self.name, equipment.slot),
+                    format!("Dequipped {} on {}.", self.name, equipment.location()),
                     LIGHT_YELLOW
                 );  
             }
@@ -206,7 +439,9 @@ impl GameObject {
                 format!("Can't dequip {:?} because it's not an Equipment.", self),
                 RED
             );
+            return false;
         };
+        true
     }
 
     pub fn get_all_equipped(&self, game: &Game) -> Vec<Equipment> {
@@ -221,13 +456,19 @@ impl GameObject {
         }
     }
 
+    // how hungry the player is, nothing else gets hungry
+    pub fn hunger(&self) -> Option<Hunger> {
+        self.nutrition.map(Hunger::from_nutrition)
+    }
+
     pub fn power(&self, game: &Game) -> i32 {
         let base_power = self.fighter.map_or(0, |f| f.base_power);
         let bonus: i32 = self.get_all_equipped(game)
             .iter()
             .map(|e| e.power_bonus)
             .sum();
-        base_power + bonus
+        let penalty = self.hunger().map_or(0, |h| h.power_penalty());
+        base_power + bonus - penalty
     }
 
     pub fn defense(&self, game: &Game) -> i32 {
@@ -237,7 +478,32 @@ impl GameObject {
             .iter()
             .map(|e| e.defense_bonus)
             .sum();
-        base_defense + bonus
+        let mut penalty = self.hunger().map_or(0, |h| h.defense_penalty());
+        if self.name == "player" {
+            penalty += encumbrance(game, self).defense_penalty();
+        }
+        base_defense + bonus - penalty
+    }
+
+    // The player slowly heals over time, and faster as they level up.  More
+    // hit points heal more at a time, and a starving player doesn't heal at all.
+    pub fn regenerate(&mut self, game: &Game) {
+        let every = cmp::max(MIN_REGEN_TURNS, BASE_REGEN_TURNS - self.level) as u32;
+        if game.turn.is_multiple_of(every) && self.hunger() != Some(Hunger::Starving) {
+            let amount = 1 + self.max_hp(game) / 100;
+            self.heal(amount, game);
+        }
+    }
+
+    pub fn has_perk(&self, perk: Perk) -> bool {
+        self.perks.as_ref().is_some_and(|p| p.taken.contains(&perk))
+    }
+
+    // the chance out of 100 of landing a critical hit
+    pub fn critical_chance(&self) -> i32 {
+        let class_chance = if self.class == Some(Class::Rogue) { ROGUE_CRITICAL_CHANCE } else { 0 };
+        let perk_chance = if self.has_perk(Perk::Precision) { 10 } else { 0 };
+        class_chance + perk_chance
     }
 
     pub fn max_hp(&self, game: &Game) -> i32 {
@@ -269,10 +535,25 @@ pub fn is_blocked(x: i32, y: i32, map: &Map, objects: &[GameObject]) -> bool {
         .any(|object| object.blocks && object.pos() == (x, y))
 }
 
-pub fn player_move_or_attack(dx: i32, dy: i32, game: &mut Game, objects: &mut [GameObject]) {
+pub fn player_move_or_attack(
+    dx: i32,
+    dy: i32,
+    tcod: &mut Tcod,
+    game: &mut Game,
+    objects: &mut [GameObject]
+) {
     let x = objects[PLAYER].x + dx;
     let y = objects[PLAYER].y + dy;
 
+    // bumping into a shopkeeper opens the shop instead of attacking
+    let shop_id = objects
+        .iter()
+        .position(|object| object.shop.is_some() && object.pos() == (x, y));
+    if let Some(shop_id) = shop_id {
+        visit_shop(shop_id, tcod, game, objects);
+        return;
+    }
+
     let target_id = objects
         .iter()
         .position(|object| object.fighter.is_some() && object.pos() == (x, y));
@@ -345,58 +626,107 @@ fn monster_death(monster: &mut GameObject, game: &mut Game) {
     monster.fighter = None;
     monster.ai = None;
     monster.name = format!("remains of {}", monster.name);
+    // the remains can be picked up and eaten
+    monster.item = Some(Item::Corpse);
+}
+
+fn pluralize(name: &str) -> String {
+    // "scroll of fireball" becomes "scrolls of fireball"
+    match name.find(" of ").or_else(|| name.find(" labelled ")) {
+        Some(index) => format!("{}s{}", &name[..index], &name[index..]),
+        None => format!("{}s", name),
+    }
+}
+
+// whether there's room for the item, either on an existing stack or in a free slot
+pub fn has_room_for(item: &GameObject, game: &Game) -> bool {
+    game.inventory.len() < 26 || game.inventory.iter().any(|other| other.stacks_with(item))
+}
+
+// puts an item in the inventory, on its stack if there is one
+pub fn add_to_inventory(item: GameObject, game: &mut Game) {
+    if let Some(stack_id) = game.inventory.iter().position(|other| other.stacks_with(&item)) {
+        game.inventory[stack_id].count += item.count;
+        return;
+    }
+
+    let index = game.inventory.len();
+    let mut equipment = item.equipment;
+    game.inventory.push(item);
+
+    // automatically equip if teh corresponding equipment slots are unused
+    if let Some(equipment) = equipment.as_mut() {
+        equipment.slot = slot_for(equipment, &game.inventory);
+        if get_conflicting_equipment(equipment, &game.inventory).is_empty() {
+            game.inventory[index].equipment = Some(*equipment);
+            game.inventory[index].equip(&mut game.messages);
+        }
+    }
 }
 
 pub fn pick_item_up(object_id: usize, game: &mut Game, objects: &mut Vec<GameObject>) {
-    if game.inventory.len() >= 26 {
-        game.messages.add(format!("Your inventory is full, cannot pick up {}.", objects[object_id].name), RED);
+    // gold goes straight into the purse
+    if objects[object_id].item == Some(Item::Gold) {
+        let gold = objects.swap_remove(object_id);
+        game.gold += gold.count;
+        game.messages.add(format!("You picked up {}.", gold.amount_name(game)), GOLD);
+        return;
+    }
+
+    // there's always room on an existing stack, even when the inventory is full
+    if !has_room_for(&objects[object_id], game) {
+        let name = objects[object_id].known_name(game);
+        game.messages.add(format!("Your inventory is full, cannot pick up {}.", name), RED);
+    } else if !can_carry(&objects[object_id], game, &objects[PLAYER]) {
+        let name = objects[object_id].known_name(game);
+        game.messages.add(format!("The {} is too heavy for you to carry.", name), RED);
     } else {
         let item = objects.swap_remove(object_id);
-        game.messages
-            .add(format!("You picked up a {}!", item.name), GREEN);
-        let index = game.inventory.len();
-        let slot = item.equipment.map(|e| e.slot);
-        game.inventory.push(item);
-
-        // automatically equip if teh corresponding equipment slot is unused
-        if let Some(slot) = slot {
-            if get_equipped_in_slot(slot, &game.inventory).is_none() {
-                game.inventory[index].equip(&mut game.messages);
-            }
-        }
+        let name = item.amount_name(game);
+        game.messages.add(format!("You picked up {}!", name), GREEN);
+        add_to_inventory(item, game);
     }
 }
 
+// Handles every level the player has gained since the last check, with a perk
+// screen for each one.
 pub fn level_up(tcod: &mut Tcod, game: &mut Game, objects: &mut [GameObject]) {
-    let player = &mut objects[PLAYER];
-    let level_up_xp = LEVEL_UP_BASE + player.level * LEVEL_UP_FACTOR;
-    if player.fighter.as_ref().map_or(0, |f| f.xp) >= level_up_xp {
+    let class = match objects[PLAYER].class {
+        Some(class) => class,
+        None => return
+    };
+    loop {
+        let rules = game.progression.rules(class).clone();
+        let player = &mut objects[PLAYER];
+        let level_up_xp = rules.xp_to_next(player.level);
+        if player.fighter.as_ref().map_or(0, |f| f.xp) < level_up_xp {
+            break;
+        }
+
         player.level += 1;
         game.messages.add(format!(
             "Your battle skills grow stronger!  You reached level {}!",
             player.level
         ), YELLOW);
 
-        let choice = level_up_menu(player, &mut tcod.root);
         let fighter = player.fighter.as_mut().unwrap();
         fighter.xp -= level_up_xp;
-        match choice.unwrap() {
-            0 => {
-                fighter.base_max_hp += 20;
-                fighter.hp += 20;
-            }
-            1 => {
-                fighter.base_power += 1;
-            }
-            2 => {
-                fighter.base_defense += 1;
-            }
-            _ => unreachable!()
+        fighter.base_max_hp += rules.hp_per_level;
+        fighter.hp += rules.hp_per_level;
+        if let Some(caster) = player.caster.as_mut() {
+            caster.max_mana += rules.mana_per_level;
+            caster.mana += rules.mana_per_level;
         }
+
+        // perk points can be spent now or saved for later
+        player.perks.as_mut().unwrap().points += rules.perk_points_per_level;
+        perk_screen(tcod, game, objects);
     }
 }
 
-enum UseResult {
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum UseResult {
+    // consumed, or one charge used for items with charges
     UsedUp,
     UsedAndKept,
     Cancelled,
@@ -406,18 +736,63 @@ pub fn use_item(inventory_id: usize, tcod: &mut Tcod, game: &mut Game, objects:
     use Item::*;
     // just call the "use_function" if it is defined
     if let Some(item) = game.inventory[inventory_id].item {
+        if game.inventory[inventory_id].charges == Some(0) {
+            game.messages.add(
+                format!("The {} has no charges left.", game.inventory[inventory_id].known_name(game)),
+                WHITE,
+            );
+            return;
+        }
         let on_use = match item {
             Heal => cast_heal,
             Lightning => cast_lightning,
             Confuse => cast_confuse,
             Fireball => cast_fireball,
-            Sword => toggle_equipment,
-            Shield => toggle_equipment
+            Sword | Shield | Helmet | Armor | Boots | Ring | Amulet | Greatsword | Torch
+            | Lantern => toggle_equipment,
+            WandLightning => cast_lightning,
+            WandConfuse => cast_confuse,
+            WandDigging => cast_dig,
+            Recharge => cast_recharge,
+            RemoveCurse => cast_remove_curse,
+            Identify => cast_identify,
+            Ration | Corpse => eat_food,
+            SpellbookLightning | SpellbookConfuse | SpellbookFireball => read_spellbook,
+            // gold never makes it into the inventory
+            Gold => return
         };
-        match on_use(inventory_id, tcod, game, objects) {
+        let was_identified = game.identification.is_identified(item);
+        let mut result = on_use(inventory_id, tcod, game, objects);
+        // an unknown item gives itself away as it's used, so backing out
+        // afterwards still uses it up rather than giving a free look
+        if result == UseResult::Cancelled && !was_identified {
+            game.messages.add(
+                format!("The {} is wasted.", game.inventory[inventory_id].known_name(game)),
+                WHITE,
+            );
+            result = UseResult::UsedUp;
+        }
+        if let UseResult::UsedUp | UseResult::UsedAndKept = result {
+            // using an item reveals what kind of item it was
+            if game.identification.identify(item) {
+                game.messages.add(
+                    format!("That was a {}.", game.inventory[inventory_id].name),
+                    LIGHT_CYAN,
+                );
+            }
+        }
+        match result {
             UseResult::UsedUp => {
                 // destroy after use, unless it was cancelled for some reason
-                game.inventory.remove(inventory_id);
+                // a stack only loses the one item that was used
+                // and charged items are kept with one charge less
+                if let Some(charges) = game.inventory[inventory_id].charges.as_mut() {
+                    *charges -= 1;
+                } else if game.inventory[inventory_id].count > 1 {
+                    game.inventory[inventory_id].count -= 1;
+                } else {
+                    game.inventory.remove(inventory_id);
+                }
             }
             UseResult::UsedAndKept => {} // nop
             UseResult::Cancelled => {
@@ -426,21 +801,31 @@ pub fn use_item(inventory_id: usize, tcod: &mut Tcod, game: &mut Game, objects:
         }
     } else {
         game.messages.add(
-            format!("The {} cannot be used.", game.inventory[inventory_id].name),
+            format!("The {} cannot be used.", game.inventory[inventory_id].known_name(game)),
             WHITE,
         );
     }
 }
 
-pub fn drop_item(inventory_id: usize, game: &mut Game, objects: &mut Vec<GameObject>) {
-    let mut item = game.inventory.remove(inventory_id);
-    if item.equipment.is_some() {
-        item.dequip(&mut game.messages);
+pub fn drop_item(inventory_id: usize, amount: i32, game: &mut Game, objects: &mut Vec<GameObject>) {
+    if game.inventory[inventory_id].equipment.is_some()
+        && !game.inventory[inventory_id].dequip(&mut game.messages)
+    {
+        return;
     }
+    let mut item = if amount < game.inventory[inventory_id].count {
+        // split the dropped items off of the stack
+        game.inventory[inventory_id].count -= amount;
+        let mut dropped = game.inventory[inventory_id].clone();
+        dropped.count = amount;
+        dropped
+    } else {
+        game.inventory.remove(inventory_id)
+    };
 
     item.set_pos(objects[PLAYER].x, objects[PLAYER].y);
-    game.messages
-        .add(format!("You dropped a {}.", item.name), YELLOW);
+    let name = item.amount_name(game);
+    game.messages.add(format!("You dropped {}.", name), YELLOW);
     objects.push(item);
 }
 
@@ -471,7 +856,11 @@ fn cast_lightning(
     game: &mut Game,
     objects: &mut [GameObject],
 ) -> UseResult {
+    lightning_bolt(tcod, game, objects)
+}
 
+// the effects below are shared by the scrolls, the wands and the spells
+pub fn lightning_bolt(tcod: &mut Tcod, game: &mut Game, objects: &mut [GameObject]) -> UseResult {
     // find closest enemy withing range and damage it
     let monster_id = closest_monster(tcod, objects, LIGHTNING_RANGE);
     if let Some(monster_id) = monster_id {
@@ -499,9 +888,13 @@ fn cast_confuse(
     game: &mut Game,
     objects: &mut [GameObject],
 ) -> UseResult {
+    confuse(tcod, game, objects)
+}
+
+pub fn confuse(tcod: &mut Tcod, game: &mut Game, objects: &mut [GameObject]) -> UseResult {
     // ask the player for a target to confuse
     game.messages.add(
-        "Left-click an enemy to confuse it, or right-click to cancel.",
+        "Choose an enemy to confuse, or press Escape to cancel.",
         LIGHT_CYAN,
     );
     // find closest enemy and confuse it
@@ -534,23 +927,32 @@ fn cast_fireball(
     game: &mut Game,
     objects: &mut [GameObject],
 ) -> UseResult {
+    fireball(tcod, game, objects)
+}
+
+pub fn fireball(tcod: &mut Tcod, game: &mut Game, objects: &mut [GameObject]) -> UseResult {
     game.messages.add(
-        "Left-click a target tile for the fireball, or right-click to cancel.", 
+        "Choose a target tile for the fireball, or press Escape to cancel.", 
         LIGHT_CYAN);
     let (x,y) = match target_tile(tcod, game, objects, None) {
         Some(tile_pos) => tile_pos,
         None => return UseResult::Cancelled
     };
+    let radius = if objects[PLAYER].has_perk(Perk::Pyromancy) {
+        FIREBALL_RADIUS + 1
+    } else {
+        FIREBALL_RADIUS
+    };
     game.messages.add(
         format!(
             "The fireball explodes, burning everything within {} tiles!",
-            FIREBALL_RADIUS
+            radius
         ), ORANGE
     );
 
     let mut xp_to_gain = 0;
     for (id, obj) in objects.iter_mut().enumerate() {
-        if obj.distance(x,y) <= FIREBALL_RADIUS as f32 && obj.fighter.is_some() {
+        if obj.distance(x,y) <= radius as f32 && obj.fighter.is_some() {
             game.messages.add(
                 format!(
                     "The {} gets burned for {} hit points.", 
@@ -570,6 +972,189 @@ fn cast_fireball(
     UseResult::UsedUp
 }
 
+fn cast_dig(
+    _inventory_id: usize,
+    tcod: &mut Tcod,
+    game: &mut Game,
+    objects: &mut [GameObject],
+) -> UseResult {
+    game.messages.add(
+        "Choose a tile to dig towards, or press Escape to cancel.",
+        LIGHT_CYAN
+    );
+    let (target_x, target_y) = match target_tile(tcod, game, objects, None) {
+        Some(tile_pos) => tile_pos,
+        None => return UseResult::Cancelled
+    };
+    let (dx, dy) = (
+        (target_x - objects[PLAYER].x).signum(),
+        (target_y - objects[PLAYER].y).signum()
+    );
+    if (dx, dy) == (0, 0) {
+        return UseResult::Cancelled;
+    }
+
+    // carve a straight tunnel, but never through the outer wall of the map
+    let (mut x, mut y) = objects[PLAYER].pos();
+    for _ in 0..DIG_RANGE {
+        x += dx;
+        y += dy;
+        if x <= 0 || y <= 0 || x >= MAP_WIDTH - 1 || y >= MAP_HEIGHT - 1 {
+            break;
+        }
+        let tile = &mut game.game_map[x as usize][y as usize];
+        tile.blocked = false;
+        tile.block_sight = false;
+    }
+    initialize_fov(tcod, &game.game_map);
+    compute_fov(tcod, game, objects);
+    game.messages.add("The rock crumbles away before you!", LIGHT_YELLOW);
+    UseResult::UsedUp
+}
+
+fn cast_recharge(
+    inventory_id: usize,
+    tcod: &mut Tcod,
+    game: &mut Game,
+    objects: &mut [GameObject],
+) -> UseResult {
+    use rand::Rng;
+
+    let wand_id = inventory_menu(
+        game,
+        &objects[PLAYER],
+        "Choose an item to recharge, or any other key to cancel.\n",
+        &mut tcod.root,
+    );
+    let wand_id = match wand_id {
+        Some(wand_id) if wand_id != inventory_id => wand_id,
+        _ => return UseResult::Cancelled
+    };
+    let name = game.inventory[wand_id].known_name(game);
+    match game.inventory[wand_id].charges.as_mut() {
+        Some(charges) => {
+            *charges += rand::thread_rng().gen_range(RECHARGE_MIN, RECHARGE_MAX + 1);
+            game.messages.add(format!("The {} hums with new energy.", name), LIGHT_BLUE);
+            UseResult::UsedUp
+        }
+        None => {
+            game.messages.add(format!("The {} can't be recharged.", name), RED);
+            UseResult::Cancelled
+        }
+    }
+}
+
+fn cast_remove_curse(
+    _inventory_id: usize,
+    _tcod: &mut Tcod,
+    game: &mut Game,
+    _objects: &mut [GameObject],
+) -> UseResult {
+    // lift the curse from everything the player is wearing
+    let mut uncursed = false;
+    for item in game.inventory.iter_mut() {
+        if let Some(equipment) = item.equipment.as_mut() {
+            if equipment.equipped && equipment.cursed {
+                equipment.cursed = false;
+                uncursed = true;
+            }
+        }
+    }
+    if uncursed {
+        game.messages.add("You feel like someone is watching over you.", LIGHT_VIOLET);
+    } else {
+        game.messages.add("You feel a malignant aura leave you... but nothing happens.", WHITE);
+    }
+    UseResult::UsedUp
+}
+
+fn cast_identify(
+    inventory_id: usize,
+    tcod: &mut Tcod,
+    game: &mut Game,
+    objects: &mut [GameObject],
+) -> UseResult {
+    let target_id = inventory_menu(
+        game,
+        &objects[PLAYER],
+        "Choose an item to identify, or any other key to cancel.\n",
+        &mut tcod.root,
+    );
+    let target_id = match target_id {
+        Some(target_id) if target_id != inventory_id => target_id,
+        _ => return UseResult::Cancelled
+    };
+
+    // reveals the kind of a potion or scroll, or whether equipment is cursed
+    if let Some(item) = game.inventory[target_id].item {
+        game.identification.identify(item);
+    }
+    if let Some(equipment) = game.inventory[target_id].equipment.as_mut() {
+        equipment.curse_known = true;
+    }
+    let item = &game.inventory[target_id];
+    let not_cursed = match item.equipment {
+        Some(equipment) if !equipment.cursed => ", and it's not cursed",
+        _ => ""
+    };
+    game.messages.add(
+        format!("It is {}{}.", item.amount_name(game), not_cursed),
+        LIGHT_CYAN
+    );
+    UseResult::UsedUp
+}
+
+fn eat_food(
+    inventory_id: usize,
+    _tcod: &mut Tcod,
+    game: &mut Game,
+    objects: &mut [GameObject],
+) -> UseResult {
+    let amount = match game.inventory[inventory_id].item {
+        Some(Item::Corpse) => CORPSE_NUTRITION,
+        _ => RATION_NUTRITION
+    };
+    let name = game.inventory[inventory_id].known_name(game);
+    if let Some(nutrition) = objects[PLAYER].nutrition.as_mut() {
+        if *nutrition >= MAX_NUTRITION - amount / 2 {
+            game.messages.add("You are too full to eat any more.", WHITE);
+            return UseResult::Cancelled;
+        }
+        *nutrition = cmp::min(*nutrition + amount, MAX_NUTRITION);
+        game.messages.add(format!("You eat the {}. Delicious!", name), LIGHT_GREEN);
+        return UseResult::UsedUp;
+    }
+    UseResult::Cancelled
+}
+
+fn read_spellbook(
+    inventory_id: usize,
+    _tcod: &mut Tcod,
+    game: &mut Game,
+    objects: &mut [GameObject],
+) -> UseResult {
+    let spell = match game.inventory[inventory_id].item {
+        Some(Item::SpellbookLightning) => Spell::Lightning,
+        Some(Item::SpellbookConfuse) => Spell::Confuse,
+        Some(Item::SpellbookFireball) => Spell::Fireball,
+        _ => return UseResult::Cancelled
+    };
+    let caster = match objects[PLAYER].caster.as_mut() {
+        Some(caster) => caster,
+        None => return UseResult::Cancelled
+    };
+    if caster.spells.contains(&spell) {
+        game.messages.add(format!("You already know {}.", spell), WHITE);
+        return UseResult::Cancelled;
+    }
+    caster.spells.push(spell);
+    game.messages.add(
+        format!("You learn {}, the book crumbles to dust.", spell),
+        LIGHT_BLUE
+    );
+    UseResult::UsedUp
+}
+
 fn closest_monster(tcod: &Tcod, objects: &[GameObject], max_range: i32) -> Option<usize> {
     let mut closest_enemy = None;
     let mut closest_dist = (max_range + 1) as f32;
@@ -580,7 +1165,7 @@ fn closest_monster(tcod: &Tcod, objects: &[GameObject], max_range: i32) -> Optio
         if (id != PLAYER)
             && object.fighter.is_some()
             && object.ai.is_some()
-            && tcod.fov.is_in_fov(object.x, object.y)
+            && tcod.sight.is_visible(object.x, object.y)
         {
             let dist = objects[PLAYER].distance_to(object);
             if dist < closest_dist {
@@ -592,42 +1177,6 @@ fn closest_monster(tcod: &Tcod, objects: &[GameObject], max_range: i32) -> Optio
     closest_enemy
 }
 
-pub fn target_tile(
-    tcod: &mut Tcod,
-    game: &mut Game,
-    objects: &[GameObject],
-    max_range: Option<f32>
-) -> Option<(i32,i32)> {
-    use tcod::input::KeyCode::Escape;
-    use tcod::input::{self, Event};
-
-    loop {
-        tcod.root.flush();
-        let event = input::check_for_event(input::KEY_PRESS | input::MOUSE).map(|e|e.1);
-        match event {
-            Some(Event::Mouse(m)) => tcod.mouse = m,
-            Some(Event::Key(k)) => tcod.key = k,
-            None => tcod.key = Default::default()
-        }
-
-        render_all(tcod, game, objects, false);
-
-        let (x,y) = (tcod.mouse.cx as i32, tcod.mouse.cy as i32);
-
-        // accept the target if the player clicked in FOV, and in case a range
-        // is specified, if it's in that range
-        let in_fov = (x < MAP_WIDTH) && (y < MAP_HEIGHT) && tcod.fov.is_in_fov(x,y);
-        let in_range = max_range.map_or(true, |range| objects[PLAYER].distance(x,y) <= range);
-        if tcod.mouse.lbutton_pressed && in_fov && in_range {
-            return Some((x,y));
-        }
-
-        if tcod.mouse.rbutton_pressed || tcod.key.code == Escape {
-            return None;
-        }
-    }
-}
-
 fn target_monster(tcod: &mut Tcod, game: &mut Game, objects: &[GameObject], max_range: Option<f32>) -> Option<usize> {
     loop {
         match target_tile(tcod, game, objects, max_range) {
@@ -657,7 +1206,22 @@ fn toggle_equipment(
     if equipment.equipped {
         game.inventory[inventory_id].dequip(&mut game.messages);
     } else {
-        if let Some(current) = get_equipped_in_slot(equipment.slot, &game.inventory) {
+        // take off whatever is in the way, e.g. both hands for a two-handed weapon
+        let mut equipment = equipment;
+        equipment.slot = slot_for(&equipment, &game.inventory);
+        game.inventory[inventory_id].equipment = Some(equipment);
+        // nothing comes off if any of it is cursed, so the player isn't left half undressed
+        let conflicting = get_conflicting_equipment(&equipment, &game.inventory);
+        let cursed = conflicting
+            .iter()
+            .cloned()
+            .find(|&current| game.inventory[current].equipment.is_some_and(|e| e.cursed));
+        if let Some(cursed) = cursed {
+            // refuses, and tells the player why
+            game.inventory[cursed].dequip(&mut game.messages);
+            return UseResult::UsedAndKept;
+        }
+        for current in conflicting {
             game.inventory[current].dequip(&mut game.messages);
         }
         game.inventory[inventory_id].equip(&mut game.messages);
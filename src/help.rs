@@ -0,0 +1,38 @@
+use tcod::colors::*;
+use tcod::console::*;
+use crate::game::*;
+use crate::keymap::Action;
+
+const HELP_WIDTH: i32 = 64;
+// the keys are listed in a column this wide, the descriptions after them
+const KEYS_WIDTH: usize = 24;
+
+// Lists every command with the keys it's bound to, over the game.  It's built
+// from the keymap, so rebound keys show up as they are.
+pub fn help_screen(tcod: &mut Tcod) {
+    let height = Action::ALL.len() as i32 + 4;
+    let mut window = Offscreen::new(HELP_WIDTH, height);
+
+    window.set_default_foreground(WHITE);
+    window.print_ex(HELP_WIDTH / 2, 0, BackgroundFlag::None, TextAlignment::Center, "Commands");
+    for (index, &action) in Action::ALL.iter().enumerate() {
+        let keys = tcod.keymap.keys_for(action);
+        let keys = if keys.is_empty() { "-".to_string() } else { keys.join(", ") };
+        let line = format!("{:<width$} {}", keys, action.description(), width = KEYS_WIDTH);
+        window.print_ex(1, index as i32 + 2, BackgroundFlag::None, TextAlignment::Left, line);
+    }
+    window.set_default_foreground(LIGHT_GREY);
+    window.print_ex(
+        HELP_WIDTH / 2,
+        height - 1,
+        BackgroundFlag::None,
+        TextAlignment::Center,
+        "Press any key to continue",
+    );
+
+    let x = SCREEN_WIDTH / 2 - HELP_WIDTH / 2;
+    let y = SCREEN_HEIGHT / 2 - height / 2;
+    blit(&window, (0, 0), (HELP_WIDTH, height), &mut tcod.root, (x, y), 1.0, 0.7);
+    tcod.root.flush();
+    tcod.root.wait_for_keypress(true);
+}
@@ -0,0 +1,118 @@
+use rand::Rng;
+use tcod::colors::*;
+use crate::game::Game;
+use crate::game_object::GameObject;
+
+pub const START_NUTRITION: i32 = 1000;
+pub const MAX_NUTRITION: i32 = 2000;
+pub const RATION_NUTRITION: i32 = 800;
+pub const CORPSE_NUTRITION: i32 = 250;
+
+const SATIATED: i32 = 1500;
+const HUNGRY: i32 = 300;
+const WEAK: i32 = 100;
+const FAINTING: i32 = 30;
+
+// chance out of 100, per turn, of passing out while fainting
+const FAINT_CHANCE: i32 = 10;
+// the turns the monsters get while the player is passed out
+const FAINT_TURNS: i32 = 3;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Hunger {
+    Satiated,
+    NotHungry,
+    Hungry,
+    Weak,
+    Fainting,
+    Starving
+}
+
+impl Hunger {
+    pub fn from_nutrition(nutrition: i32) -> Self {
+        match nutrition {
+            n if n > SATIATED => Hunger::Satiated,
+            n if n > HUNGRY => Hunger::NotHungry,
+            n if n > WEAK => Hunger::Hungry,
+            n if n > FAINTING => Hunger::Weak,
+            n if n > 0 => Hunger::Fainting,
+            _ => Hunger::Starving,
+        }
+    }
+
+    pub fn power_penalty(self) -> i32 {
+        match self {
+            Hunger::Weak => 1,
+            Hunger::Fainting | Hunger::Starving => 2,
+            _ => 0,
+        }
+    }
+
+    pub fn defense_penalty(self) -> i32 {
+        match self {
+            Hunger::Fainting | Hunger::Starving => 1,
+            _ => 0,
+        }
+    }
+
+    // the colour of the hunger indicator in the panel
+    pub fn color(self) -> Color {
+        match self {
+            Hunger::Satiated => LIGHT_GREEN,
+            Hunger::NotHungry => WHITE,
+            Hunger::Hungry => YELLOW,
+            Hunger::Weak => ORANGE,
+            Hunger::Fainting | Hunger::Starving => RED,
+        }
+    }
+}
+
+impl std::fmt::Display for Hunger {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match *self {
+            Hunger::Satiated => write!(f, "Satiated"),
+            Hunger::NotHungry => write!(f, "Not hungry"),
+            Hunger::Hungry => write!(f, "Hungry"),
+            Hunger::Weak => write!(f, "Weak"),
+            Hunger::Fainting => write!(f, "Fainting"),
+            Hunger::Starving => write!(f, "Starving"),
+        }
+    }
+}
+
+// Burns one turn's worth of nutrition.  Returns the number of extra turns the
+// monsters get because the player fainted.
+pub fn digest(player: &mut GameObject, game: &mut Game) -> i32 {
+    let nutrition = match player.nutrition.as_mut() {
+        Some(nutrition) => nutrition,
+        None => return 0
+    };
+    let before = Hunger::from_nutrition(*nutrition);
+    *nutrition -= 1;
+    let after = Hunger::from_nutrition(*nutrition);
+
+    if before != after {
+        let message = match after {
+            Hunger::Hungry => "You are getting hungry.",
+            Hunger::Weak => "You feel weak from hunger.",
+            Hunger::Fainting => "You are fainting from lack of food!",
+            Hunger::Starving => "You are starving to death!",
+            _ => "",
+        };
+        if !message.is_empty() {
+            game.messages.add(message, after.color());
+        }
+    }
+
+    match after {
+        Hunger::Fainting if rand::thread_rng().gen_range(0, 100) < FAINT_CHANCE => {
+            game.messages.add("You faint from lack of food.", RED);
+            FAINT_TURNS
+        }
+        Hunger::Starving => {
+            player.take_damage(1, game);
+            0
+        }
+        _ => 0,
+    }
+}
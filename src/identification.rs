@@ -0,0 +1,75 @@
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use crate::game_object::Item;
+
+// the item kinds that have to be identified before their true name is shown
+const POTIONS: &[Item] = &[Item::Heal];
+pub const SCROLLS: &[Item] = &[
+    Item::Lightning,
+    Item::Confuse,
+    Item::Fireball,
+    Item::Recharge,
+    Item::RemoveCurse,
+    Item::Identify,
+];
+
+const POTION_APPEARANCES: &[&str] = &[
+    "murky", "bubbling", "fizzy", "smoky", "golden", "milky", "glowing", "oily",
+];
+
+const SCROLL_LABELS: &[&str] = &[
+    "XYZZY", "FOOBIE BLETCH", "ELBIB YLOH", "VERR YED HORRE", "KIRJE", "PRATYAVAYAH",
+    "ZELGO MER", "JUYED AWK YACC", "NR 9", "THARR",
+];
+
+// Unidentified items are shown by a random appearance that is shuffled for
+// every new game.  Using an item identifies its kind for the rest of the game.
+#[derive(Serialize, Deserialize)]
+pub struct Identification {
+    appearances: Vec<(Item, String)>,
+    identified: Vec<Item>,
+}
+
+impl Identification {
+    pub fn new() -> Self {
+        let mut potions = POTION_APPEARANCES.to_vec();
+        let mut scrolls = SCROLL_LABELS.to_vec();
+        rand::thread_rng().shuffle(&mut potions);
+        rand::thread_rng().shuffle(&mut scrolls);
+
+        let potion_names = potions.iter().map(|name| format!("{} potion", name));
+        let scroll_names = scrolls.iter().map(|label| format!("scroll labelled {}", label));
+        let appearances = POTIONS
+            .iter()
+            .cloned()
+            .zip(potion_names)
+            .chain(SCROLLS.iter().cloned().zip(scroll_names))
+            .collect();
+
+        Identification {
+            appearances,
+            identified: vec![],
+        }
+    }
+
+    // the appearance of an item kind, if that kind needs to be identified at all
+    pub fn appearance(&self, item: Item) -> Option<&str> {
+        self.appearances
+            .iter()
+            .find(|(kind, _)| *kind == item)
+            .map(|(_, appearance)| appearance.as_str())
+    }
+
+    pub fn is_identified(&self, item: Item) -> bool {
+        self.appearance(item).is_none() || self.identified.contains(&item)
+    }
+
+    // returns true if the item kind was not known before
+    pub fn identify(&mut self, item: Item) -> bool {
+        if self.is_identified(item) {
+            return false;
+        }
+        self.identified.push(item);
+        true
+    }
+}
@@ -0,0 +1,576 @@
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fs::File;
+use std::io::{ErrorKind, Read, Write};
+use serde::{Deserialize, Serialize};
+use tcod::colors::*;
+use tcod::console::*;
+use tcod::input::{Key, KeyCode};
+use crate::game::*;
+use crate::menu::{menu, msgbox};
+
+// where the player's own bindings are kept, next to the save game
+const KEYMAP_FILE: &str = "keymap.json";
+const LOAD_ERROR_WIDTH: i32 = 50;
+const CONFIRM_WIDTH: i32 = 50;
+
+// everything the player can do with a key press
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Action {
+    MoveNorth,
+    MoveSouth,
+    MoveWest,
+    MoveEast,
+    MoveNorthWest,
+    MoveNorthEast,
+    MoveSouthWest,
+    MoveSouthEast,
+    RunNorth,
+    RunSouth,
+    RunWest,
+    RunEast,
+    RunNorthWest,
+    RunNorthEast,
+    RunSouthWest,
+    RunSouthEast,
+    Wait,
+    PickUp,
+    Drop,
+    Inventory,
+    TakeStairs,
+    TravelStairs,
+    Equipment,
+    Character,
+    Cast,
+    Ability,
+    Perks,
+    Rest,
+    Explore,
+    Examine,
+    Messages,
+    Overview,
+    Help,
+    Encyclopedia,
+    ToggleFullscreen,
+    SaveAndQuit
+}
+
+impl Action {
+    pub const ALL: [Action; 36] = [
+        Action::MoveNorth,
+        Action::MoveSouth,
+        Action::MoveWest,
+        Action::MoveEast,
+        Action::MoveNorthWest,
+        Action::MoveNorthEast,
+        Action::MoveSouthWest,
+        Action::MoveSouthEast,
+        Action::RunNorth,
+        Action::RunSouth,
+        Action::RunWest,
+        Action::RunEast,
+        Action::RunNorthWest,
+        Action::RunNorthEast,
+        Action::RunSouthWest,
+        Action::RunSouthEast,
+        Action::Wait,
+        Action::PickUp,
+        Action::Drop,
+        Action::Inventory,
+        Action::TakeStairs,
+        Action::TravelStairs,
+        Action::Equipment,
+        Action::Character,
+        Action::Cast,
+        Action::Ability,
+        Action::Perks,
+        Action::Rest,
+        Action::Explore,
+        Action::Examine,
+        Action::Messages,
+        Action::Overview,
+        Action::Help,
+        Action::Encyclopedia,
+        Action::ToggleFullscreen,
+        Action::SaveAndQuit,
+    ];
+
+    // the step taken by the movement actions
+    pub fn direction(self) -> Option<(i32, i32)> {
+        match self {
+            Action::MoveNorth => Some((0, -1)),
+            Action::MoveSouth => Some((0, 1)),
+            Action::MoveWest => Some((-1, 0)),
+            Action::MoveEast => Some((1, 0)),
+            Action::MoveNorthWest => Some((-1, -1)),
+            Action::MoveNorthEast => Some((1, -1)),
+            Action::MoveSouthWest => Some((-1, 1)),
+            Action::MoveSouthEast => Some((1, 1)),
+            _ => None,
+        }
+    }
+
+    // the way the run actions set off in
+    pub fn run_direction(self) -> Option<(i32, i32)> {
+        match self {
+            Action::RunNorth => Some((0, -1)),
+            Action::RunSouth => Some((0, 1)),
+            Action::RunWest => Some((-1, 0)),
+            Action::RunEast => Some((1, 0)),
+            Action::RunNorthWest => Some((-1, -1)),
+            Action::RunNorthEast => Some((1, -1)),
+            Action::RunSouthWest => Some((-1, 1)),
+            Action::RunSouthEast => Some((1, 1)),
+            _ => None,
+        }
+    }
+
+    pub fn description(self) -> &'static str {
+        match self {
+            Action::MoveNorth => "Move or attack north",
+            Action::MoveSouth => "Move or attack south",
+            Action::MoveWest => "Move or attack west",
+            Action::MoveEast => "Move or attack east",
+            Action::MoveNorthWest => "Move or attack north-west",
+            Action::MoveNorthEast => "Move or attack north-east",
+            Action::MoveSouthWest => "Move or attack south-west",
+            Action::MoveSouthEast => "Move or attack south-east",
+            Action::RunNorth => "Run north",
+            Action::RunSouth => "Run south",
+            Action::RunWest => "Run west",
+            Action::RunEast => "Run east",
+            Action::RunNorthWest => "Run north-west",
+            Action::RunNorthEast => "Run north-east",
+            Action::RunSouthWest => "Run south-west",
+            Action::RunSouthEast => "Run south-east",
+            Action::Wait => "Wait a turn",
+            Action::PickUp => "Pick up an item",
+            Action::Drop => "Drop an item",
+            Action::Inventory => "Use an item from the inventory",
+            Action::TakeStairs => "Take the stairs down",
+            Action::TravelStairs => "Walk to the stairs",
+            Action::Equipment => "Show equipment",
+            Action::Character => "Show character information",
+            Action::Cast => "Cast a spell",
+            Action::Ability => "Use an ability",
+            Action::Perks => "Spend perk points",
+            Action::Rest => "Rest until healed",
+            Action::Explore => "Explore the level",
+            Action::Examine => "Look around",
+            Action::Messages => "Show the message log",
+            Action::Overview => "Show the whole level",
+            Action::Help => "Show this help",
+            Action::Encyclopedia => "Show the monsters and items seen so far",
+            Action::ToggleFullscreen => "Toggle fullscreen",
+            Action::SaveAndQuit => "Save and leave the game",
+        }
+    }
+}
+
+// the keys that go by a name rather than the character they type
+const NAMED_KEYS: &[(KeyCode, &str)] = &[
+    (KeyCode::Escape, "Escape"),
+    (KeyCode::Backspace, "Backspace"),
+    (KeyCode::Tab, "Tab"),
+    (KeyCode::Enter, "Enter"),
+    (KeyCode::PageUp, "PageUp"),
+    (KeyCode::PageDown, "PageDown"),
+    (KeyCode::End, "End"),
+    (KeyCode::Home, "Home"),
+    (KeyCode::Up, "Up"),
+    (KeyCode::Left, "Left"),
+    (KeyCode::Right, "Right"),
+    (KeyCode::Down, "Down"),
+    (KeyCode::Insert, "Insert"),
+    (KeyCode::Delete, "Delete"),
+    (KeyCode::NumPad0, "NumPad0"),
+    (KeyCode::NumPad1, "NumPad1"),
+    (KeyCode::NumPad2, "NumPad2"),
+    (KeyCode::NumPad3, "NumPad3"),
+    (KeyCode::NumPad4, "NumPad4"),
+    (KeyCode::NumPad5, "NumPad5"),
+    (KeyCode::NumPad6, "NumPad6"),
+    (KeyCode::NumPad7, "NumPad7"),
+    (KeyCode::NumPad8, "NumPad8"),
+    (KeyCode::NumPad9, "NumPad9"),
+    (KeyCode::NumPadEnter, "NumPadEnter"),
+    (KeyCode::F1, "F1"),
+    (KeyCode::F2, "F2"),
+    (KeyCode::F3, "F3"),
+    (KeyCode::F4, "F4"),
+    (KeyCode::F5, "F5"),
+    (KeyCode::F6, "F6"),
+    (KeyCode::F7, "F7"),
+    (KeyCode::F8, "F8"),
+    (KeyCode::F9, "F9"),
+    (KeyCode::F10, "F10"),
+    (KeyCode::F11, "F11"),
+    (KeyCode::F12, "F12"),
+];
+
+// How a key is written in the keymap file, like "k", "<", "Up" or "alt+Enter".
+// Characters come in as text, which already tells upper and lower case apart.
+// The raw key press that comes along with the text has no name, so a binding
+// isn't triggered twice.
+pub fn key_name(key: Key) -> Option<String> {
+    let (name, is_text) = if key.code == KeyCode::Text {
+        let text = key.text();
+        if text.trim().is_empty() {
+            return None;
+        }
+        (text.to_string(), true)
+    } else {
+        let name = NAMED_KEYS.iter().find(|&&(code, _)| code == key.code)?.1;
+        (name.to_string(), false)
+    };
+
+    let mut modifiers = String::new();
+    if key.ctrl {
+        modifiers.push_str("ctrl+");
+    }
+    if key.alt {
+        modifiers.push_str("alt+");
+    }
+    if key.shift && !is_text {
+        modifiers.push_str("shift+");
+    }
+    Some(modifiers + &name)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Preset {
+    Numpad,
+    ViKeys,
+    Wasd
+}
+
+impl std::fmt::Display for Preset {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match *self {
+            Preset::Numpad => write!(f, "numpad"),
+            Preset::ViKeys => write!(f, "vi-keys"),
+            Preset::Wasd => write!(f, "WASD"),
+        }
+    }
+}
+
+// which key does what
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Keymap {
+    bindings: BTreeMap<String, Action>,
+}
+
+impl Keymap {
+    pub fn preset(preset: Preset) -> Self {
+        use Action::*;
+
+        // the arrows and the other named keys work the same everywhere
+        let mut bindings: Vec<(&str, Action)> = vec![
+            ("Up", MoveNorth),
+            ("Down", MoveSouth),
+            ("Left", MoveWest),
+            ("Right", MoveEast),
+            ("Home", MoveNorthWest),
+            ("PageUp", MoveNorthEast),
+            ("End", MoveSouthWest),
+            ("PageDown", MoveSouthEast),
+            ("shift+Up", RunNorth),
+            ("shift+Down", RunSouth),
+            ("shift+Left", RunWest),
+            ("shift+Right", RunEast),
+            ("shift+Home", RunNorthWest),
+            ("shift+PageUp", RunNorthEast),
+            ("shift+End", RunSouthWest),
+            ("shift+PageDown", RunSouthEast),
+            ("o", Overview),
+            ("?", Help),
+            ("/", Encyclopedia),
+            ("alt+Enter", ToggleFullscreen),
+            ("Escape", SaveAndQuit),
+        ];
+        let commands = vec![
+            ("g", PickUp),
+            ("d", Drop),
+            ("i", Inventory),
+            ("<", TakeStairs),
+            ("_", TravelStairs),
+            ("e", Equipment),
+            ("c", Character),
+            ("z", Cast),
+            ("a", Ability),
+            ("p", Perks),
+            ("r", Rest),
+            ("X", Explore),
+            ("x", Examine),
+            ("m", Messages),
+        ];
+        match preset {
+            Preset::Numpad => {
+                bindings.extend(vec![
+                    ("NumPad8", MoveNorth),
+                    ("NumPad2", MoveSouth),
+                    ("NumPad4", MoveWest),
+                    ("NumPad6", MoveEast),
+                    ("NumPad7", MoveNorthWest),
+                    ("NumPad9", MoveNorthEast),
+                    ("NumPad1", MoveSouthWest),
+                    ("NumPad3", MoveSouthEast),
+                    ("shift+NumPad8", RunNorth),
+                    ("shift+NumPad2", RunSouth),
+                    ("shift+NumPad4", RunWest),
+                    ("shift+NumPad6", RunEast),
+                    ("shift+NumPad7", RunNorthWest),
+                    ("shift+NumPad9", RunNorthEast),
+                    ("shift+NumPad1", RunSouthWest),
+                    ("shift+NumPad3", RunSouthEast),
+                    ("NumPad5", Wait),
+                ]);
+                bindings.extend(commands);
+            }
+            Preset::ViKeys => {
+                bindings.extend(vec![
+                    ("k", MoveNorth),
+                    ("j", MoveSouth),
+                    ("h", MoveWest),
+                    ("l", MoveEast),
+                    ("y", MoveNorthWest),
+                    ("u", MoveNorthEast),
+                    ("b", MoveSouthWest),
+                    ("n", MoveSouthEast),
+                    ("K", RunNorth),
+                    ("J", RunSouth),
+                    ("H", RunWest),
+                    ("L", RunEast),
+                    ("Y", RunNorthWest),
+                    ("U", RunNorthEast),
+                    ("B", RunSouthWest),
+                    ("N", RunSouthEast),
+                    (".", Wait),
+                ]);
+                bindings.extend(commands);
+            }
+            Preset::Wasd => {
+                // the letters under the left hand move, so some commands move out of the way
+                bindings.extend(vec![
+                    ("w", MoveNorth),
+                    ("s", MoveSouth),
+                    ("a", MoveWest),
+                    ("d", MoveEast),
+                    ("q", MoveNorthWest),
+                    ("e", MoveNorthEast),
+                    ("z", MoveSouthWest),
+                    ("c", MoveSouthEast),
+                    // E and C are taken, so those two only run with the shifted arrows
+                    ("W", RunNorth),
+                    ("S", RunSouth),
+                    ("A", RunWest),
+                    ("D", RunEast),
+                    ("Q", RunNorthWest),
+                    ("Z", RunSouthWest),
+                    ("x", Wait),
+                    ("g", PickUp),
+                    ("t", Drop),
+                    ("i", Inventory),
+                    ("<", TakeStairs),
+                    ("_", TravelStairs),
+                    ("E", Equipment),
+                    ("C", Character),
+                    ("f", Cast),
+                    ("F", Ability),
+                    ("p", Perks),
+                    ("r", Rest),
+                    ("X", Explore),
+                    ("l", Examine),
+                    ("m", Messages),
+                ]);
+            }
+        }
+        Keymap {
+            bindings: bindings.into_iter().map(|(key, action)| (key.to_string(), action)).collect(),
+        }
+    }
+
+    // The player's keymap, or the numpad preset if they haven't made one yet.
+    // A keymap that can't be read falls back to the preset too, but says so.
+    pub fn load(root: &mut Root) -> Self {
+        match Self::load_file() {
+            Ok(Some(keymap)) => keymap,
+            Ok(None) => Self::preset(Preset::Numpad),
+            Err(error) => {
+                let text = format!(
+                    "\nCouldn't read {}: {}\n\nUsing the {} preset instead.  Leaving the Keybindings screen will replace the file.\n",
+                    KEYMAP_FILE, error, Preset::Numpad
+                );
+                msgbox(&text, LOAD_ERROR_WIDTH, root);
+                Self::preset(Preset::Numpad)
+            }
+        }
+    }
+
+    fn load_file() -> Result<Option<Self>, Box<dyn Error>> {
+        let mut json = String::new();
+        let mut file = match File::open(KEYMAP_FILE) {
+            Ok(file) => file,
+            Err(error) if error.kind() == ErrorKind::NotFound => return Ok(None),
+            Err(error) => return Err(error.into()),
+        };
+        file.read_to_string(&mut json)?;
+        Ok(Some(serde_json::from_str(&json)?))
+    }
+
+    pub fn save(&self) -> Result<(), Box<dyn Error>> {
+        let json = serde_json::to_string_pretty(self)?;
+        let mut file = File::create(KEYMAP_FILE)?;
+        file.write_all(json.as_bytes())?;
+        Ok(())
+    }
+
+    pub fn action(&self, key: Key) -> Option<Action> {
+        key_name(key).and_then(|name| self.bindings.get(&name).cloned())
+    }
+
+    // all the keys bound to the action, for help and the keybindings screen
+    pub fn keys_for(&self, action: Action) -> Vec<&str> {
+        self.bindings
+            .iter()
+            .filter(|&(_, &a)| a == action)
+            .map(|(key, _)| key.as_str())
+            .collect()
+    }
+
+    pub fn bound_to(&self, key: &str) -> Option<Action> {
+        self.bindings.get(key).cloned()
+    }
+
+    // The key does the action now, along with the action's other keys.  A key
+    // only does one thing, so it's taken from the action it had, unless that
+    // would leave that action with no key at all.
+    pub fn rebind(&mut self, action: Action, key: String) -> Result<(), String> {
+        match self.bound_to(&key) {
+            Some(other) if other != action && self.keys_for(other).len() == 1 => Err(format!(
+                "{} is the only key for \"{}\".  Give that another key first.",
+                key, other.description()
+            )),
+            _ => {
+                self.bindings.insert(key, action);
+                Ok(())
+            }
+        }
+    }
+
+    // takes the key away from the action, as long as it has another one
+    pub fn unbind(&mut self, action: Action, key: &str) -> Result<(), String> {
+        if self.bound_to(key) != Some(action) {
+            return Err(format!("{} isn't a key for \"{}\".", key, action.description()));
+        }
+        if self.keys_for(action).len() == 1 {
+            return Err(format!("{} is the only key for \"{}\".", key, action.description()));
+        }
+        self.bindings.remove(key);
+        Ok(())
+    }
+}
+
+// shows the prompt at the bottom of the keybindings screen and waits for a key,
+// None if the player pressed Escape
+fn prompt_for_key(root: &mut Root, prompt: &str) -> Option<String> {
+    root.set_default_foreground(YELLOW);
+    root.print_ex(SCREEN_WIDTH / 2, SCREEN_HEIGHT - 3, BackgroundFlag::None, TextAlignment::Center, prompt);
+    root.flush();
+    loop {
+        let key = root.wait_for_keypress(true);
+        if key.code == KeyCode::Escape {
+            return None;
+        }
+        if let Some(name) = key_name(key) {
+            return Some(name);
+        }
+    }
+}
+
+// Lists every action with its keys.  Up and down pick an action, Enter adds a
+// key to it and Delete takes one away, and the presets replace the whole
+// keymap.  The keymap is saved on the way out.
+pub fn keybindings_screen(tcod: &mut Tcod) {
+    use tcod::input::KeyCode::*;
+
+    let mut selected = 0;
+    loop {
+        tcod.root.set_default_background(BLACK);
+        tcod.root.clear();
+        tcod.root.set_default_foreground(WHITE);
+        tcod.root.print_ex(
+            SCREEN_WIDTH / 2,
+            0,
+            BackgroundFlag::None,
+            TextAlignment::Center,
+            "Keybindings",
+        );
+        for (index, &action) in Action::ALL.iter().enumerate() {
+            let y = index as i32 + 2;
+            let flag = if index == selected { BackgroundFlag::Set } else { BackgroundFlag::None };
+            tcod.root.set_default_background(DARKER_BLUE);
+            tcod.root.print_ex(2, y, flag, TextAlignment::Left, action.description());
+            tcod.root.print_ex(40, y, flag, TextAlignment::Left, tcod.keymap.keys_for(action).join(", "));
+        }
+        tcod.root.set_default_foreground(LIGHT_GREY);
+        tcod.root.print_ex(
+            1,
+            SCREEN_HEIGHT - 1,
+            BackgroundFlag::None,
+            TextAlignment::Left,
+            format!(
+                "Up/Down choose, Enter add key, Del remove key, F1 {}, F2 {}, F3 {}, Esc done",
+                Preset::Numpad, Preset::ViKeys, Preset::Wasd
+            ),
+        );
+        tcod.root.flush();
+
+        let key = tcod.root.wait_for_keypress(true);
+        match key.code {
+            Up | NumPad8 => selected = selected.max(1) - 1,
+            Down | NumPad2 => selected = (selected + 1).min(Action::ALL.len() - 1),
+            // Escape leaves the bindings as they were
+            Enter | NumPadEnter => {
+                let action = Action::ALL[selected];
+                let prompt = format!("Press a new key for: {}", action.description());
+                if let Some(key) = prompt_for_key(&mut tcod.root, &prompt) {
+                    let other = tcod.keymap.bound_to(&key).filter(|&other| other != action);
+                    let confirmed = other.is_none_or(|other| {
+                        let question = format!("\n{} is already a key for \"{}\".\n", key, other.description());
+                        let choice = format!("Use it for \"{}\" instead", action.description());
+                        menu(&question, &[choice], CONFIRM_WIDTH, &mut tcod.root) == Some(0)
+                    });
+                    if confirmed {
+                        if let Err(problem) = tcod.keymap.rebind(action, key) {
+                            msgbox(&format!("\n{}\n", problem), CONFIRM_WIDTH, &mut tcod.root);
+                        }
+                    }
+                }
+            }
+            Backspace | Delete => {
+                let action = Action::ALL[selected];
+                let prompt = format!("Press the key to remove from: {}", action.description());
+                if let Some(key) = prompt_for_key(&mut tcod.root, &prompt) {
+                    if let Err(problem) = tcod.keymap.unbind(action, &key) {
+                        msgbox(&format!("\n{}\n", problem), CONFIRM_WIDTH, &mut tcod.root);
+                    }
+                }
+            }
+            F1 | F2 | F3 => {
+                let preset = match key.code {
+                    F1 => Preset::Numpad,
+                    F2 => Preset::ViKeys,
+                    _ => Preset::Wasd,
+                };
+                tcod.keymap = Keymap::preset(preset);
+            }
+            Escape => break,
+            _ => {}
+        }
+    }
+
+    if tcod.keymap.save().is_err() {
+        msgbox("\nCouldn't save the keybindings.\n", 24, &mut tcod.root);
+    }
+}
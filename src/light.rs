@@ -0,0 +1,84 @@
+use tcod::colors::*;
+use tcod::map::Map as FovMap;
+use crate::game::Game;
+use crate::game_object::{GameObject, Item};
+use crate::map::*;
+
+pub const TORCH_RADIUS: i32 = 8;
+pub const TORCH_FUEL: i32 = 1000;
+pub const LANTERN_RADIUS: i32 = 12;
+pub const LANTERN_FUEL: i32 = 2000;
+
+// without a light the player can only make out what's right next to them
+const DARKNESS_RADIUS: i32 = 1;
+
+// What the player can actually see.  A tile has to be in line of sight, and
+// either in a lit room or close enough to be lit by the player's own light.
+pub struct Sight {
+    visible: Vec<Vec<bool>>,
+}
+
+impl Sight {
+    pub fn new() -> Self {
+        Sight {
+            visible: vec![vec![false; MAP_HEIGHT as usize]; MAP_WIDTH as usize],
+        }
+    }
+
+    pub fn compute(&mut self, fov: &FovMap, map: &Map, player: &GameObject, light_radius: i32) {
+        for x in 0..MAP_WIDTH {
+            for y in 0..MAP_HEIGHT {
+                let lit = map[x as usize][y as usize].lit
+                    || player.distance(x, y) <= light_radius as f32;
+                self.visible[x as usize][y as usize] = fov.is_in_fov(x, y) && lit;
+            }
+        }
+    }
+
+    pub fn is_visible(&self, x: i32, y: i32) -> bool {
+        x >= 0 && y >= 0 && x < MAP_WIDTH && y < MAP_HEIGHT && self.visible[x as usize][y as usize]
+    }
+}
+
+// how far the player's equipped light reaches
+pub fn light_radius(game: &Game) -> i32 {
+    game.inventory
+        .iter()
+        .filter_map(|item| item.equipment)
+        .filter(|e| e.equipped && e.fuel.is_none_or(|fuel| fuel > 0))
+        .map(|e| e.light_radius)
+        .max()
+        .unwrap_or(0)
+        .max(DARKNESS_RADIUS)
+}
+
+// Equipped light sources burn a turn's worth of fuel.  Torches are gone once
+// they burn out, an empty lantern stays around but gives no light.
+pub fn burn_light(game: &mut Game) {
+    let mut burnt_out = vec![];
+    for (inventory_id, item) in game.inventory.iter_mut().enumerate() {
+        if let Some(equipment) = item.equipment.as_mut() {
+            if !equipment.equipped {
+                continue;
+            }
+            if let Some(fuel) = equipment.fuel.as_mut() {
+                if *fuel > 0 {
+                    *fuel -= 1;
+                    if *fuel == 0 {
+                        burnt_out.push(inventory_id);
+                    }
+                }
+            }
+        }
+    }
+
+    for inventory_id in burnt_out.into_iter().rev() {
+        if game.inventory[inventory_id].item == Some(Item::Torch) {
+            let torch = game.inventory.remove(inventory_id);
+            game.messages.add(format!("Your {} burns out.", torch.name), ORANGE);
+        } else {
+            let name = game.inventory[inventory_id].name.clone();
+            game.messages.add(format!("Your {} has run out of oil.", name), ORANGE);
+        }
+    }
+}
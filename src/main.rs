@@ -8,22 +8,50 @@ mod menu;
 mod panel;
 mod transition;
 mod equipment;
+mod identification;
+mod hunger;
+mod shop;
+mod encumbrance;
+mod light;
+mod class;
+mod spell;
+mod perk;
+mod progression;
+mod activity;
+mod message_log;
+mod targeting;
+mod keymap;
+mod help;
+mod encyclopedia;
+mod camera;
+mod overview;
+mod options;
+mod path;
 
 fn main() {
     tcod::system::set_fps(game::LIMIT_FPS);
 
-    let root = Root::initializer()
+    let mut root = Root::initializer()
         .font("assets/arial12x12.png", FontLayout::Tcod)
         .font_type(FontType::Greyscale)
         .size(game::SCREEN_WIDTH, game::SCREEN_HEIGHT)
         .title("Rust/libtcod tutorial")
         .init();
 
+    // the player's own settings, any problem with them is shown before the menu
+    let keymap = keymap::Keymap::load(&mut root);
+    let options = options::Options::load(&mut root);
+
     let mut tcod = game::Tcod { 
         root, 
         con: Offscreen::new(map::MAP_WIDTH, map::MAP_HEIGHT), 
         panel: Offscreen::new(game::SCREEN_WIDTH, panel::PANEL_HEIGHT),
         fov: FovMap::new(map::MAP_WIDTH, map::MAP_HEIGHT),
+        sight: light::Sight::new(),
+        cursor: None,
+        camera: Default::default(),
+        keymap,
+        options,
         key: Default::default(),
         mouse: Default::default()
     };
@@ -6,8 +6,9 @@ use std::cmp;
 use tcod::colors::*;
 use tcod::map::FovAlgorithm;
 
-pub const MAP_WIDTH: i32 = 80;
-pub const MAP_HEIGHT: i32 = 43;
+// the map is bigger than the screen, the camera shows the part around the player
+pub const MAP_WIDTH: i32 = 120;
+pub const MAP_HEIGHT: i32 = 72;
 
 pub const COLOR_DARK_WALL: Color = Color { r: 0, g: 0, b: 100 };
 pub const COLOR_LIGHT_WALL: Color = Color {
@@ -28,11 +29,13 @@ pub const COLOR_LIGHT_GROUND: Color = Color {
 
 const ROOM_MAX_SIZE: i32 = 10;
 const ROOM_MIN_SIZE: i32 = 6;
-const MAX_ROOMS: i32 = 30;
+const MAX_ROOMS: i32 = 60;
 
 pub const FOV_ALGO: FovAlgorithm = FovAlgorithm::Basic;
 pub const FOV_LIGHT_WALLS: bool = true;
-pub const TORCH_RADIUS: i32 = 10;
+
+// chance out of 100 that a room on a dark level is lit anyway
+const LIT_ROOM_CHANCE: i32 = 25;
 
 // alias Vec<Vec<Tile>> to "Map"
 pub type Map = Vec<Vec<Tile>>;
@@ -43,6 +46,7 @@ pub struct Tile {
     pub blocked: bool,
     pub block_sight: bool,
     pub explored: bool,
+    pub lit: bool,
 }
 
 impl Tile {
@@ -51,6 +55,7 @@ impl Tile {
             blocked: false,
             block_sight: false,
             explored: false,
+            lit: false,
         }
     }
 
@@ -59,11 +64,15 @@ impl Tile {
             blocked: true,
             block_sight: true,
             explored: false,
+            lit: false,
         }
     }
 }
 
 pub fn make_map(objects: &mut Vec<GameObject>, level: u32) -> Map {
+    use crate::transition::*;
+    use crate::shop::new_shopkeeper;
+
     // fill map with "blocked" tiles
     let mut map = vec![vec![Tile::wall(); MAP_HEIGHT as usize]; MAP_WIDTH as usize];
     let mut rooms = vec![];
@@ -71,6 +80,17 @@ pub fn make_map(objects: &mut Vec<GameObject>, level: u32) -> Map {
     // for "next levels", remove any existing objects except the player
     objects.retain(|i| i.name == "player");
 
+    // deeper levels are more often dark, with only a few lit rooms
+    let dark_chance = from_dungeon_level(
+        &[
+            Transition { level: 3, value: 20 },
+            Transition { level: 6, value: 40 },
+            Transition { level: 9, value: 60 },
+        ],
+        level
+    );
+    let dark_level = rand::thread_rng().gen_range(0, 100) < dark_chance;
+
     for _ in 0..MAX_ROOMS {
         // random width and height of room
         let w = rand::thread_rng().gen_range(ROOM_MIN_SIZE, ROOM_MAX_SIZE + 1);
@@ -88,6 +108,9 @@ pub fn make_map(objects: &mut Vec<GameObject>, level: u32) -> Map {
         // No intersections, lets create the new room
         if !failed {
             create_room(new_room, &mut map);
+            if !dark_level || rand::thread_rng().gen_range(0, 100) < LIT_ROOM_CHANCE {
+                light_room(new_room, &mut map);
+            }
             place_objects(new_room, &map, objects, level);
 
             let (new_x, new_y) = new_room.center();
@@ -119,6 +142,16 @@ pub fn make_map(objects: &mut Vec<GameObject>, level: u32) -> Map {
     stairs.always_visible = true;
     objects.push(stairs);
 
+    // some levels have a shop in one of the rooms between the start and the stairs
+    let shop_chance = from_dungeon_level(&[Transition { level: 2, value: 40 }], level);
+    if rooms.len() > 2 && rand::thread_rng().gen_range(0, 100) < shop_chance {
+        let shop_room = rooms[rand::thread_rng().gen_range(1, rooms.len() - 1)];
+        // the shopkeeper doesn't share the room with monsters or loot
+        objects.retain(|o| o.name == "player" || !shop_room.contains(o.x, o.y));
+        let (shop_x, shop_y) = shop_room.center();
+        objects.push(new_shopkeeper(shop_x, shop_y, level));
+    }
+
     map
 }
 
@@ -145,6 +178,10 @@ impl Rect {
         let center_y = (self.y1 + self.y2) / 2;
         (center_x, center_y)
     }
+    // whether the position is on the floor inside of the room
+    pub fn contains(&self, x: i32, y: i32) -> bool {
+        x > self.x1 && x < self.x2 && y > self.y1 && y < self.y2
+    }
     pub fn intersects_with(&self, other: &Rect) -> bool {
         (self.x1 <= other.x2)
             && (self.x2 >= other.x1)
@@ -161,6 +198,15 @@ fn create_room(room: Rect, map: &mut Map) {
     }
 }
 
+// lights up the whole room, walls included
+fn light_room(room: Rect, map: &mut Map) {
+    for x in room.x1..=room.x2 {
+        for y in room.y1..=room.y2 {
+            map[x as usize][y as usize].lit = true;
+        }
+    }
+}
+
 fn create_h_tunnel(x1: i32, x2: i32, y: i32, map: &mut Map) {
     for x in cmp::min(x1, x2)..(cmp::max(x1, x2) + 1) {
         map[x as usize][y as usize] = Tile::empty();
@@ -176,7 +222,6 @@ fn create_v_tunnel(y1: i32, y2: i32, x: i32, map: &mut Map) {
 fn place_objects(room: Rect, map: &Map, objects: &mut Vec<GameObject>, level: u32) {
     use rand::distributions::{IndependentSample, Weighted, WeightedChoice};
     use crate::transition::*;
-    use crate::equipment::*;
 
     // maximum number of monsters per room
     let max_monsters = from_dungeon_level(
@@ -204,11 +249,11 @@ fn place_objects(room: Rect, map: &Map, objects: &mut Vec<GameObject>, level: u3
     let monster_chances = &mut [
         Weighted {
             weight: 80,
-            item: "orc",
+            item: Monster::Orc,
         },
         Weighted {
             weight: troll_chance,
-            item: "troll",
+            item: Monster::Troll,
         },
     ];
     let monster_choice = WeightedChoice::new(monster_chances);
@@ -220,38 +265,8 @@ fn place_objects(room: Rect, map: &Map, objects: &mut Vec<GameObject>, level: u3
 
         // only place it if the tile is not blocked
         if !is_blocked(x, y, map, objects) {
-            let mut monster = match monster_choice.ind_sample(&mut rand::thread_rng()) {
-                "orc" => {
-                    let mut orc = GameObject::new(x, y, 'o', "orc", DESATURATED_GREEN, true);
-                    orc.fighter = Some(Fighter {
-                        base_max_hp: 20,
-                        hp: 20,
-                        base_defense: 0,
-                        base_power: 4,
-                        xp: 35,
-                        on_death: DeathCallback::Monster,
-                    });
-                    orc.ai = Some(Ai::Basic);
-                    orc
-                }
-                "troll" => {
-                    // 20% chance of getting a troll
-                    let mut troll = GameObject::new(x, y, 'T', "troll", DARKER_GREEN, true);
-                    troll.fighter = Some(Fighter {
-                        base_max_hp: 30,
-                        hp: 30,
-                        base_defense: 2,
-                        base_power: 8,
-                        xp: 100,
-                        on_death: DeathCallback::Monster,
-                    });
-                    troll.ai = Some(Ai::Basic);
-                    troll
-                }
-                _ => unreachable!(),
-            };
-            monster.alive = true;
-            objects.push(monster);
+            let monster = monster_choice.ind_sample(&mut rand::thread_rng());
+            objects.push(new_monster(monster, x, y));
         }
     }
 
@@ -264,6 +279,26 @@ fn place_objects(room: Rect, map: &Map, objects: &mut Vec<GameObject>, level: u3
     );
 
     // choose random number of items
+    let num_items = rand::thread_rng().gen_range(0, max_items + 1);
+    for _ in 0..num_items {
+        // choose random spot for this item
+        let x = rand::thread_rng().gen_range(room.x1 + 1, room.x2);
+        let y = rand::thread_rng().gen_range(room.y1 + 1, room.y2);
+
+        // only place it if the tle is not blocked
+        if !is_blocked(x, y, map, objects) {
+            let mut item = new_item(random_item(level), x, y, level);
+            item.always_visible = true;
+            objects.push(item);
+        }
+    }
+}
+
+// picks a random item kind, deeper levels have better items
+pub fn random_item(level: u32) -> Item {
+    use rand::distributions::{IndependentSample, Weighted, WeightedChoice};
+    use crate::transition::*;
+
     let item_chances = &mut [
         Weighted {
             weight: 35,
@@ -288,60 +323,313 @@ fn place_objects(room: Rect, map: &Map, objects: &mut Vec<GameObject>, level: u3
         Weighted {
             weight: from_dungeon_level(&[Transition { level: 8, value: 15}], level), 
             item: Item::Shield
+        },
+        Weighted {
+            weight: from_dungeon_level(&[Transition { level: 3, value: 5}], level),
+            item: Item::WandLightning
+        },
+        Weighted {
+            weight: from_dungeon_level(&[Transition { level: 2, value: 5}], level),
+            item: Item::WandConfuse
+        },
+        Weighted {
+            weight: from_dungeon_level(&[Transition { level: 2, value: 5}], level),
+            item: Item::WandDigging
+        },
+        Weighted {
+            weight: from_dungeon_level(&[Transition { level: 3, value: 8}], level),
+            item: Item::Recharge
+        },
+        Weighted {
+            weight: from_dungeon_level(&[Transition { level: 2, value: 10}], level),
+            item: Item::Helmet
+        },
+        Weighted {
+            weight: from_dungeon_level(&[Transition { level: 5, value: 10}], level),
+            item: Item::Armor
+        },
+        Weighted {
+            weight: from_dungeon_level(&[Transition { level: 3, value: 10}], level),
+            item: Item::Boots
+        },
+        Weighted {
+            weight: from_dungeon_level(&[Transition { level: 6, value: 5}], level),
+            item: Item::Ring
+        },
+        Weighted {
+            weight: from_dungeon_level(&[Transition { level: 7, value: 5}], level),
+            item: Item::Amulet
+        },
+        Weighted {
+            weight: from_dungeon_level(&[Transition { level: 6, value: 5}], level),
+            item: Item::Greatsword
+        },
+        Weighted {
+            weight: from_dungeon_level(&[Transition { level: 3, value: 8}], level),
+            item: Item::RemoveCurse
+        },
+        Weighted {
+            weight: from_dungeon_level(&[Transition { level: 2, value: 10}], level),
+            item: Item::Identify
+        },
+        Weighted {
+            weight: 20,
+            item: Item::Ration
+        },
+        Weighted {
+            weight: 25,
+            item: Item::Gold
+        },
+        Weighted {
+            weight: 15,
+            item: Item::Torch
+        },
+        Weighted {
+            weight: from_dungeon_level(&[Transition { level: 4, value: 5}], level),
+            item: Item::Lantern
+        },
+        Weighted {
+            weight: from_dungeon_level(&[Transition { level: 2, value: 3}], level),
+            item: Item::SpellbookLightning
+        },
+        Weighted {
+            weight: from_dungeon_level(&[Transition { level: 3, value: 3}], level),
+            item: Item::SpellbookConfuse
+        },
+        Weighted {
+            weight: from_dungeon_level(&[Transition { level: 5, value: 3}], level),
+            item: Item::SpellbookFireball
         }
     ];
-    let item_choice = WeightedChoice::new(item_chances);
+    WeightedChoice::new(item_chances).ind_sample(&mut rand::thread_rng())
+}
 
-    let num_items = rand::thread_rng().gen_range(0, max_items + 1);
-    for _ in 0..num_items {
-        // choose random spot for this item
-        let x = rand::thread_rng().gen_range(room.x1 + 1, room.x2);
-        let y = rand::thread_rng().gen_range(room.y1 + 1, room.y2);
+pub fn new_torch(x: i32, y: i32) -> GameObject {
+    use crate::equipment::*;
+    use crate::light::*;
+
+    let mut object = GameObject::new(x, y, '(', "torch", FLAME, false);
+    object.item = Some(Item::Torch);
+    let mut equipment = Equipment::new(Slot::Light, 0, 0, 0);
+    equipment.light_radius = TORCH_RADIUS;
+    equipment.fuel = Some(TORCH_FUEL);
+    object.equipment = Some(equipment);
+    object
+}
 
-        // only place it if the tle is not blocked
-        if !is_blocked(x, y, map, objects) {
-            let mut item = match item_choice.ind_sample(&mut rand::thread_rng()) {
-                Item::Heal => {
-                    let mut object = GameObject::new(x, y, '!', "healing potion", VIOLET, false);
-                    object.item = Some(Item::Heal);
-                    object
-                }
-                Item::Lightning => {
-                    let mut object =
-                        GameObject::new(x, y, '#', "scroll of lightning bolt", LIGHT_YELLOW, false);
-                    object.item = Some(Item::Lightning);
-                    object
-                }
-                Item::Fireball => {
-                    let mut object =
-                        GameObject::new(x, y, '#', "scroll of fireball", LIGHT_YELLOW, false);
-                    object.item = Some(Item::Fireball);
-                    object
-                }
-                Item::Confuse => {
-                    let mut object =
-                        GameObject::new(x, y, '#', "scroll of confusion", LIGHT_YELLOW, false);
-                    object.item = Some(Item::Confuse);
-                    object
-                }
-                Item::Sword => {
-                    let mut object =
-                        GameObject::new(x, y, '/', "sword", SKY, false);
-                    object.item = Some(Item::Sword);
-                    object.equipment = Some(Equipment { equipped: false, slot: Slot::RightHand, max_hp_bonus: 0, defense_bonus: 0, power_bonus: 3 });
-                    object
-                }
-                Item::Shield => {
-                    let mut object =
-                        GameObject::new(x, y, '[', "shield", DARKER_ORANGE, false);
-                    object.item = Some(Item::Shield);
-                    object.equipment = Some(Equipment { equipped: false, slot: Slot::LeftHand, max_hp_bonus: 0, defense_bonus: 1, power_bonus: 0 });
-                    object
-                }
-            };
+// creates an item of the given kind, equipment may come out cursed or with affixes
+pub fn new_item(item: Item, x: i32, y: i32, level: u32) -> GameObject {
+    use crate::equipment::*;
 
-            item.always_visible = true;
-            objects.push(item);
+    let mut object = base_item(item, x, y, level);
+    // lights burn out and have to come off again, so they're never cursed or enchanted
+    if object.equipment.is_some_and(|e| e.slot != Slot::Light) {
+        roll_curse(&mut object, level);
+        roll_affixes(&mut object, level);
+    }
+    object
+}
+
+// an item of the given kind as it's defined, before any curse or affix is rolled
+pub fn base_item(item: Item, x: i32, y: i32, level: u32) -> GameObject {
+    use crate::equipment::*;
+    use crate::light::*;
+
+    match item {
+        Item::Heal => {
+            let mut object = GameObject::new(x, y, '!', "healing potion", VIOLET, false);
+            object.item = Some(Item::Heal);
+            object
+        }
+        Item::Lightning => {
+            let mut object =
+                GameObject::new(x, y, '#', "scroll of lightning bolt", LIGHT_YELLOW, false);
+            object.item = Some(Item::Lightning);
+            object
+        }
+        Item::Fireball => {
+            let mut object =
+                GameObject::new(x, y, '#', "scroll of fireball", LIGHT_YELLOW, false);
+            object.item = Some(Item::Fireball);
+            object
+        }
+        Item::Confuse => {
+            let mut object =
+                GameObject::new(x, y, '#', "scroll of confusion", LIGHT_YELLOW, false);
+            object.item = Some(Item::Confuse);
+            object
+        }
+        Item::Sword => {
+            let mut object =
+                GameObject::new(x, y, '/', "sword", SKY, false);
+            object.item = Some(Item::Sword);
+            object.equipment = Some(Equipment::new(Slot::RightHand, 3, 0, 0));
+            object
+        }
+        Item::Shield => {
+            let mut object =
+                GameObject::new(x, y, '[', "shield", DARKER_ORANGE, false);
+            object.item = Some(Item::Shield);
+            object.equipment = Some(Equipment::new(Slot::LeftHand, 0, 1, 0));
+            object
+        }
+        Item::Helmet => {
+            let mut object =
+                GameObject::new(x, y, '[', "helmet", LIGHT_GREY, false);
+            object.item = Some(Item::Helmet);
+            object.equipment = Some(Equipment::new(Slot::Head, 0, 1, 0));
+            object
+        }
+        Item::Armor => {
+            let mut object =
+                GameObject::new(x, y, '[', "chain mail", GREY, false);
+            object.item = Some(Item::Armor);
+            object.equipment = Some(Equipment::new(Slot::Body, 0, 2, 0));
+            object
+        }
+        Item::Boots => {
+            let mut object =
+                GameObject::new(x, y, '[', "leather boots", SEPIA, false);
+            object.item = Some(Item::Boots);
+            object.equipment = Some(Equipment::new(Slot::Feet, 0, 1, 0));
+            object
+        }
+        Item::Ring => {
+            let mut object =
+                GameObject::new(x, y, '=', "ring of strength", GOLD, false);
+            object.item = Some(Item::Ring);
+            object.equipment = Some(Equipment::new(Slot::LeftRing, 1, 0, 0));
+            object
+        }
+        Item::Amulet => {
+            let mut object =
+                GameObject::new(x, y, '"', "amulet of vitality", LIGHT_AMBER, false);
+            object.item = Some(Item::Amulet);
+            object.equipment = Some(Equipment::new(Slot::Amulet, 0, 0, 25));
+            object
+        }
+        Item::Greatsword => {
+            let mut object =
+                GameObject::new(x, y, '|', "greatsword", LIGHT_SKY, false);
+            object.item = Some(Item::Greatsword);
+            let mut equipment = Equipment::new(Slot::RightHand, 6, 0, 0);
+            equipment.two_handed = true;
+            object.equipment = Some(equipment);
+            object
+        }
+        Item::RemoveCurse => {
+            let mut object =
+                GameObject::new(x, y, '#', "scroll of remove curse", LIGHT_YELLOW, false);
+            object.item = Some(Item::RemoveCurse);
+            object
+        }
+        Item::Identify => {
+            let mut object =
+                GameObject::new(x, y, '#', "scroll of identify", LIGHT_YELLOW, false);
+            object.item = Some(Item::Identify);
+            object
+        }
+        Item::Ration => {
+            let mut object =
+                GameObject::new(x, y, '%', "food ration", SEPIA, false);
+            object.item = Some(Item::Ration);
+            object
+        }
+        Item::Gold => {
+            let mut object = GameObject::new(x, y, '$', "gold piece", GOLD, false);
+            object.item = Some(Item::Gold);
+            object.count = rand::thread_rng().gen_range(5, 15 + 5 * level as i32);
+            object
+        }
+        Item::Torch => new_torch(x, y),
+        Item::Lantern => {
+            let mut object = GameObject::new(x, y, '(', "lantern", LIGHTER_YELLOW, false);
+            object.item = Some(Item::Lantern);
+            let mut equipment = Equipment::new(Slot::Light, 0, 0, 0);
+            equipment.light_radius = LANTERN_RADIUS;
+            equipment.fuel = Some(LANTERN_FUEL);
+            object.equipment = Some(equipment);
+            object
+        }
+        Item::SpellbookLightning => {
+            let mut object =
+                GameObject::new(x, y, '+', "spellbook of lightning bolt", LIGHT_BLUE, false);
+            object.item = Some(Item::SpellbookLightning);
+            object
+        }
+        Item::SpellbookConfuse => {
+            let mut object =
+                GameObject::new(x, y, '+', "spellbook of confusion", LIGHT_CYAN, false);
+            object.item = Some(Item::SpellbookConfuse);
+            object
+        }
+        Item::SpellbookFireball => {
+            let mut object =
+                GameObject::new(x, y, '+', "spellbook of fireball", ORANGE, false);
+            object.item = Some(Item::SpellbookFireball);
+            object
+        }
+        // corpses are only ever left behind by monsters
+        Item::Corpse => unreachable!(),
+        Item::WandLightning => {
+            let mut object =
+                GameObject::new(x, y, '~', "wand of lightning", LIGHT_BLUE, false);
+            object.item = Some(Item::WandLightning);
+            object.charges = Some(rand::thread_rng().gen_range(2, 5));
+            object
+        }
+        Item::WandConfuse => {
+            let mut object =
+                GameObject::new(x, y, '~', "wand of confusion", LIGHT_CYAN, false);
+            object.item = Some(Item::WandConfuse);
+            object.charges = Some(rand::thread_rng().gen_range(2, 5));
+            object
+        }
+        Item::WandDigging => {
+            let mut object =
+                GameObject::new(x, y, '~', "wand of digging", DARK_SEPIA, false);
+            object.item = Some(Item::WandDigging);
+            object.charges = Some(rand::thread_rng().gen_range(3, 7));
+            object
+        }
+        Item::Recharge => {
+            let mut object =
+                GameObject::new(x, y, '#', "scroll of recharging", LIGHT_YELLOW, false);
+            object.item = Some(Item::Recharge);
+            object
         }
     }
 }
+
+pub fn new_monster(monster: Monster, x: i32, y: i32) -> GameObject {
+    let mut object = match monster {
+        Monster::Orc => {
+            let mut orc = GameObject::new(x, y, 'o', "orc", DESATURATED_GREEN, true);
+            orc.fighter = Some(Fighter {
+                base_max_hp: 20,
+                hp: 20,
+                base_defense: 0,
+                base_power: 4,
+                xp: 35,
+                on_death: DeathCallback::Monster,
+            });
+            orc
+        }
+        Monster::Troll => {
+            let mut troll = GameObject::new(x, y, 'T', "troll", DARKER_GREEN, true);
+            troll.fighter = Some(Fighter {
+                base_max_hp: 30,
+                hp: 30,
+                base_defense: 2,
+                base_power: 8,
+                xp: 100,
+                on_death: DeathCallback::Monster,
+            });
+            troll
+        }
+    };
+    object.monster = Some(monster);
+    object.ai = Some(Ai::Basic);
+    object.alive = true;
+    object
+}
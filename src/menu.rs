@@ -1,18 +1,33 @@
 use crate::game::*;
 use crate::game_object::GameObject;
+use crate::class::Class;
+use crate::keymap::keybindings_screen;
+use crate::options::options_menu;
 use tcod::colors::*;
 use tcod::console::*;
 
 const INVENTORY_MENU_WIDTH: i32 = 50;
-const LEVEL_SCREEN_WIDTH: i32 = 40;
+const CLASS_SCREEN_WIDTH: i32 = 64;
 const CHARACTER_SCREEN_WIDTH: i32 = 30;
+const EQUIPMENT_SCREEN_WIDTH: i32 = 44;
 
-fn msgbox(text: &str, width: i32, root: &mut Root) {
+pub fn msgbox(text: &str, width: i32, root: &mut Root) {
     let options: &[&str] = &[];
     menu(text, options, width, root);
 }
 
-fn menu<T: AsRef<str>>(header: &str, options: &[T], width: i32, root: &mut Root) -> Option<usize> {
+pub fn menu<T: AsRef<str>>(header: &str, options: &[T], width: i32, root: &mut Root) -> Option<usize> {
+    let options: Vec<_> = options.iter().map(|option| (option.as_ref(), WHITE)).collect();
+    colored_menu(header, &options, width, root)
+}
+
+// a menu where each option is printed in its own colour
+pub fn colored_menu<T: AsRef<str>>(
+    header: &str,
+    options: &[(T, Color)],
+    width: i32,
+    root: &mut Root
+) -> Option<usize> {
     assert!(options.len() <= 26,
         "Cannot have a menu with more than 26 options."
     );
@@ -32,9 +47,10 @@ fn menu<T: AsRef<str>>(header: &str, options: &[T], width: i32, root: &mut Root)
     window.print_rect_ex(0, 0, width, height, BackgroundFlag::None, TextAlignment::Left, header);
 
     // print all the options
-    for(index, option_text) in options.iter().enumerate() {
+    for(index, (option_text, color)) in options.iter().enumerate() {
         let menu_letter = (b'a' + index as u8) as char;
         let text = format!("({}) {}", menu_letter, option_text.as_ref());
+        window.set_default_foreground(*color);
         window.print_ex(
             0,
             header_height + index as i32,
@@ -88,14 +104,16 @@ pub fn main_menu(tcod: &mut Tcod) {
         );
 
         // show options and wait for the player's choice
-        let choices = &["Play a new game", "Continue last game", "Quit"];
+        let choices = &["Play a new game", "Continue last game", "Keybindings", "Options", "Quit"];
         let choice = menu("", choices, 24, &mut tcod.root);
 
         match choice {
             Some(0) => {
                 // new game
-                let (mut game, mut objects) = new_game(tcod);
-                play_game(tcod, &mut game, &mut objects);
+                if let Some(class) = class_menu(&mut tcod.root) {
+                    let (mut game, mut objects) = new_game(tcod, class);
+                    play_game(tcod, &mut game, &mut objects);
+                }
             }
             Some(1) => {
                 match load_game() {
@@ -111,7 +129,9 @@ pub fn main_menu(tcod: &mut Tcod) {
                 
 
             }
-            Some(2) => {
+            Some(2) => keybindings_screen(tcod),
+            Some(3) => options_menu(tcod),
+            Some(4) => {
                 break; // quit
             }
             _ => {}
@@ -119,23 +139,35 @@ pub fn main_menu(tcod: &mut Tcod) {
     }
 }
 
-pub fn inventory_menu(inventory: &[GameObject], header: &str, root: &mut Root) -> Option<usize> {
+pub fn inventory_menu(game: &Game, player: &GameObject, header: &str, root: &mut Root) -> Option<usize> {
+    use crate::encumbrance::*;
+
+    let inventory = &game.inventory;
+    let header = format!(
+        "{}Weight: {}/{} ({})\n",
+        header,
+        carried_weight(game),
+        carrying_capacity(player),
+        encumbrance(game, player)
+    );
     // how a menu with each item of the inventory as an option
     let options = if inventory.len() == 0 {
-        vec!["Inventory is empty.".into()]
+        vec![("Inventory is empty.".into(), WHITE)]
     } else {
         inventory.iter().map(|item| {
             //show additional information, in case it's equipped
+            // equipment is coloured by its rarity
             match item.equipment {
                 Some(equipment) if equipment.equipped => {
-                    format!("{} (on {})", item.name, equipment.slot)
+                    (format!("{} (on {})", item.known_name(game), equipment.location()), equipment.rarity.color())
                 }
-                _ => item.name.clone()
+                Some(equipment) => (item.display_name(game), equipment.rarity.color()),
+                None => (item.display_name(game), WHITE)
             }
         }).collect()
     };
     
-    let inventory_index = menu(header, &options, INVENTORY_MENU_WIDTH, root);
+    let inventory_index = colored_menu(&header, &options, INVENTORY_MENU_WIDTH, root);
 
     // if an item was chosen, return it
     if inventory.len() > 0 {
@@ -145,45 +177,90 @@ pub fn inventory_menu(inventory: &[GameObject], header: &str, root: &mut Root) -
     }
 }
 
-pub fn level_up_menu(player: &mut GameObject, root: &mut Root) -> Option<usize> {
-    let fighter = player.fighter.as_mut().unwrap();
-    let mut choice = None;
-    while choice.is_none() {
-        choice = menu(
-            "Level up! Choose a stat to raise:\n",
-            &[
-                format!("Constitution (+20 HP, from {})", fighter.base_max_hp),
-                format!("Strength (+1 attack, from {})", fighter.base_power),
-                format!("Agility (+1 defense, from {})", fighter.base_defense),
-            ],
-            LEVEL_SCREEN_WIDTH,
-            root
-        );
+pub fn drop_amount_menu(item: &GameObject, game: &Game, root: &mut Root) -> Option<i32> {
+    let choice = menu(
+        &format!("Drop how many {}?\n", item.display_name(game)),
+        &["One".to_string(), format!("All {}", item.count)],
+        INVENTORY_MENU_WIDTH,
+        root
+    );
+    match choice {
+        Some(0) => Some(1),
+        Some(1) => Some(item.count),
+        _ => None
     }
-    choice
 }
 
-pub fn character_information_msgbox(
-    root: &mut Root,
-    game: &Game,
-    player: &GameObject, 
-    base: i32, 
-    factor: i32) {
+// Returns None if the player backs out to the main menu
+pub fn class_menu(root: &mut Root) -> Option<Class> {
+    let options: Vec<_> = Class::ALL
+        .iter()
+        .map(|class| format!("{} - {}", class, class.description()))
+        .collect();
+    menu("Choose your class:\n", &options, CLASS_SCREEN_WIDTH, root).map(|choice| Class::ALL[choice])
+}
+
+pub fn character_information_msgbox(root: &mut Root, game: &Game, player: &GameObject) {
+    use crate::encumbrance::*;
+
     let level = player.level;
-    let level_up_xp = base + player.level * factor;
+    let level_up_xp = player
+        .class
+        .map_or(0, |class| game.progression.rules(class).xp_to_next(level));
     if let Some(fighter) = player.fighter.as_ref() {
         let msg = format!(
             "Character information
             
+Class: {}
 Level: {}
 Experience: {}
 Experience to level up: {}
 
 Maximum HP: {}
+Maximum mana: {}
 Attack: {}
-Defense: {}",
-            level, fighter.xp, level_up_xp, player.max_hp(game), player.power(game), player.defense(game)
+Defense: {}
+Perk points: {}
+
+Carrying: {}/{}
+{}",
+            player.class.map_or("None".to_string(), |c| c.to_string()), level, fighter.xp, level_up_xp, player.max_hp(game),
+            player.caster.as_ref().map_or(0, |c| c.max_mana), player.power(game), player.defense(game),
+            player.perks.as_ref().map_or(0, |p| p.points),
+            carried_weight(game), carrying_capacity(player), encumbrance(game, player)
         );
         msgbox(&msg, CHARACTER_SCREEN_WIDTH, root);
     }
-}
\ No newline at end of file
+}
+
+pub fn equipment_msgbox(root: &mut Root, game: &Game, player: &GameObject) {
+    use crate::equipment::*;
+
+    // a paper doll, one line per slot from head to toe
+    let slots = [
+        ("Head", Slot::Head),
+        ("Neck", Slot::Amulet),
+        ("Body", Slot::Body),
+        ("Right hand", Slot::RightHand),
+        ("Left hand", Slot::LeftHand),
+        ("Right ring", Slot::RightRing),
+        ("Left ring", Slot::LeftRing),
+        ("Feet", Slot::Feet),
+        ("Belt", Slot::Light),
+    ];
+    let mut msg = String::from("Equipment\n\n");
+    for (label, slot) in slots.iter() {
+        let worn = get_equipped_in_slot(*slot, &game.inventory)
+            .map_or("-".to_string(), |id| game.inventory[id].known_name(game));
+        msg.push_str(&format!("{:>10}: {}\n", label, worn));
+    }
+
+    let equipped = player.get_all_equipped(game);
+    msg.push_str(&format!(
+        "\nAttack bonus: {}\nDefense bonus: {}\nHP bonus: {}",
+        equipped.iter().map(|e| e.power_bonus).sum::<i32>(),
+        equipped.iter().map(|e| e.defense_bonus).sum::<i32>(),
+        equipped.iter().map(|e| e.max_hp_bonus).sum::<i32>()
+    ));
+    msgbox(&msg, EQUIPMENT_SCREEN_WIDTH, root);
+}
@@ -0,0 +1,189 @@
+use tcod::colors::*;
+use tcod::console::*;
+use tcod::input::KeyCode::*;
+use crate::game::*;
+use crate::keymap::Action;
+
+// the lines between the title at the top and the help line at the bottom
+const LOG_HEIGHT: i32 = SCREEN_HEIGHT - 3;
+const LOG_WIDTH: i32 = SCREEN_WIDTH - 2;
+
+// one line on screen, messages longer than the screen is wide take several
+// and every turn starts with a separator that belongs to no message
+struct Line {
+    text: String,
+    color: Color,
+    message_id: Option<usize>,
+}
+
+// A full screen view of the message history, grouped by turn.  It scrolls
+// with the arrow and page keys, and '/' searches backwards through the messages.
+pub fn message_log_screen(tcod: &mut Tcod, game: &Game) {
+    let mut lines: Vec<Line> = vec![];
+    let mut last_turn = None;
+    for (message_id, message) in game.messages.iter().enumerate() {
+        if last_turn != Some(message.turn) {
+            lines.push(Line {
+                text: format!("-- Turn {} --", message.turn),
+                color: DARK_GREY,
+                message_id: None,
+            });
+            last_turn = Some(message.turn);
+        }
+        for text in wrap(&message.display(), LOG_WIDTH as usize) {
+            lines.push(Line { text, color: message.color, message_id: Some(message_id) });
+        }
+    }
+    let bottom = (lines.len() as i32 - LOG_HEIGHT).max(0);
+    let mut top = bottom;
+    let mut search = String::new();
+    let mut matches: Vec<usize> = vec![];
+
+    loop {
+        draw(&mut tcod.root, &lines, top, &search, &matches);
+        let key = tcod.root.wait_for_keypress(true);
+        match key.code {
+            Up | NumPad8 => top -= 1,
+            Down | NumPad2 => top += 1,
+            PageUp => top -= LOG_HEIGHT,
+            PageDown => top += LOG_HEIGHT,
+            Home => top = 0,
+            End => top = bottom,
+            Escape => break,
+            _ if key.printable == '/' => {
+                search = read_search(&mut tcod.root, &lines, top);
+                matches = find(game, &search);
+                if let Some(line) = previous_match(&lines, &matches, top + LOG_HEIGHT) {
+                    top = line;
+                }
+            }
+            // jump to the next older match
+            _ if key.printable == 'n' && !matches.is_empty() => {
+                if let Some(line) = previous_match(&lines, &matches, top) {
+                    top = line;
+                }
+            }
+            // or with the player's own movement keys
+            _ => {
+                if let Some((_, dy)) = tcod.keymap.action(key).and_then(Action::direction) {
+                    top += dy;
+                }
+            }
+        }
+        top = top.max(0).min(bottom);
+    }
+}
+
+fn draw(root: &mut Root, lines: &[Line], top: i32, search: &str, matches: &[usize]) {
+    root.set_default_background(BLACK);
+    root.clear();
+    root.set_default_foreground(WHITE);
+    root.print_ex(
+        SCREEN_WIDTH / 2,
+        0,
+        BackgroundFlag::None,
+        TextAlignment::Center,
+        "Message log",
+    );
+
+    for (y, line) in lines.iter().skip(top as usize).take(LOG_HEIGHT as usize).enumerate() {
+        let background = if line.message_id.is_some_and(|id| matches.contains(&id)) {
+            BackgroundFlag::Set
+        } else {
+            BackgroundFlag::None
+        };
+        root.set_default_background(DARKER_BLUE);
+        root.set_default_foreground(line.color);
+        root.print_ex(1, y as i32 + 2, background, TextAlignment::Left, &line.text);
+    }
+
+    let help = if search.is_empty() {
+        "Up/Down/PgUp/PgDn/Home/End scroll, / search, Esc close".to_string()
+    } else {
+        format!("Searching for \"{}\", n for the next match, / search, Esc close", search)
+    };
+    root.set_default_foreground(LIGHT_GREY);
+    root.print_ex(1, SCREEN_HEIGHT - 1, BackgroundFlag::None, TextAlignment::Left, help);
+    root.flush();
+}
+
+// lets the player type in what to look for, Enter to search or Escape to cancel
+fn read_search(root: &mut Root, lines: &[Line], top: i32) -> String {
+    let mut search = String::new();
+    loop {
+        draw(root, lines, top, "", &[]);
+        root.set_default_background(BLACK);
+        root.rect(0, SCREEN_HEIGHT - 1, SCREEN_WIDTH, 1, true, BackgroundFlag::Set);
+        root.set_default_foreground(WHITE);
+        root.print_ex(
+            1,
+            SCREEN_HEIGHT - 1,
+            BackgroundFlag::None,
+            TextAlignment::Left,
+            format!("Search: {}_", search),
+        );
+        root.flush();
+
+        let key = root.wait_for_keypress(true);
+        match key.code {
+            Enter | NumPadEnter => return search,
+            Escape => return String::new(),
+            Backspace => {
+                search.pop();
+            }
+            _ if !key.printable.is_control() && key.printable != '\0' => search.push(key.printable),
+            _ => {}
+        }
+    }
+}
+
+// the messages that contain the search, ignoring case
+fn find(game: &Game, search: &str) -> Vec<usize> {
+    if search.is_empty() {
+        return vec![];
+    }
+    let search = search.to_lowercase();
+    game.messages
+        .iter()
+        .enumerate()
+        .filter(|(_, message)| message.text.to_lowercase().contains(&search))
+        .map(|(message_id, _)| message_id)
+        .collect()
+}
+
+// the first line of the closest match that starts above the given line
+fn previous_match(lines: &[Line], matches: &[usize], before: i32) -> Option<i32> {
+    lines
+        .iter()
+        .enumerate()
+        .take(before.max(0) as usize)
+        .filter(|(index, line)| {
+            line.message_id.is_some_and(|id| matches.contains(&id))
+                && lines[index - 1].message_id != line.message_id
+        })
+        .map(|(index, _)| index as i32)
+        .next_back()
+}
+
+// splits the text into lines no wider than the width, between words where it can
+fn wrap(text: &str, width: usize) -> Vec<String> {
+    let mut lines = vec![];
+    let mut line = String::new();
+    for word in text.split_whitespace() {
+        if !line.is_empty() && line.len() + 1 + word.len() > width {
+            lines.push(std::mem::take(&mut line));
+        }
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        line.push_str(word);
+        while line.len() > width {
+            let rest = line.split_off(width);
+            lines.push(std::mem::replace(&mut line, rest));
+        }
+    }
+    if !line.is_empty() || lines.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
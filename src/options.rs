@@ -0,0 +1,77 @@
+use std::error::Error;
+use std::fs::File;
+use std::io::{ErrorKind, Read, Write};
+use serde::{Deserialize, Serialize};
+use tcod::console::Root;
+use crate::game::*;
+use crate::menu::{menu, msgbox};
+
+// kept next to the keymap, and like it shared by every game
+const OPTIONS_FILE: &str = "options.json";
+const OPTIONS_MENU_WIDTH: i32 = 40;
+const LOAD_ERROR_WIDTH: i32 = 50;
+
+// the player's preferences for how the game plays
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Options {
+    // whether exploring picks up the items walked over
+    pub auto_pickup: bool,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options { auto_pickup: true }
+    }
+}
+
+impl Options {
+    // The player's options, or the defaults if they haven't changed any yet.
+    // Options that can't be read fall back to the defaults too, but say so.
+    pub fn load(root: &mut Root) -> Self {
+        match Self::load_file() {
+            Ok(options) => options.unwrap_or_default(),
+            Err(error) => {
+                let text = format!(
+                    "\nCouldn't read {}: {}\n\nUsing the default options instead.  Leaving the Options screen will replace the file.\n",
+                    OPTIONS_FILE, error
+                );
+                msgbox(&text, LOAD_ERROR_WIDTH, root);
+                Self::default()
+            }
+        }
+    }
+
+    fn load_file() -> Result<Option<Self>, Box<dyn Error>> {
+        let mut json = String::new();
+        let mut file = match File::open(OPTIONS_FILE) {
+            Ok(file) => file,
+            Err(error) if error.kind() == ErrorKind::NotFound => return Ok(None),
+            Err(error) => return Err(error.into()),
+        };
+        file.read_to_string(&mut json)?;
+        Ok(Some(serde_json::from_str(&json)?))
+    }
+
+    pub fn save(&self) -> Result<(), Box<dyn Error>> {
+        let json = serde_json::to_string_pretty(self)?;
+        let mut file = File::create(OPTIONS_FILE)?;
+        file.write_all(json.as_bytes())?;
+        Ok(())
+    }
+}
+
+// choosing an option toggles it, anything else leaves and saves them
+pub fn options_menu(tcod: &mut Tcod) {
+    loop {
+        let on_off = |on: bool| if on { "on" } else { "off" };
+        let choices = [format!("Auto-pickup while exploring: {}", on_off(tcod.options.auto_pickup))];
+        match menu("Options\n", &choices, OPTIONS_MENU_WIDTH, &mut tcod.root) {
+            Some(0) => tcod.options.auto_pickup = !tcod.options.auto_pickup,
+            _ => break,
+        }
+    }
+
+    if tcod.options.save().is_err() {
+        msgbox("\nCouldn't save the options.\n", 24, &mut tcod.root);
+    }
+}
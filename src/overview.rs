@@ -0,0 +1,169 @@
+use tcod::colors::*;
+use tcod::console::*;
+use crate::game::*;
+use crate::game_object::*;
+use crate::light::Sight;
+use crate::map::*;
+
+// the legend goes down the right hand side of the screen
+const LEGEND_WIDTH: i32 = 18;
+const LEGEND_X: i32 = SCREEN_WIDTH - LEGEND_WIDTH;
+// the rows left for the map under the title
+const OVERVIEW_HEIGHT: i32 = SCREEN_HEIGHT - 3;
+
+// What the overview shows in a cell.  A cell covers a block of tiles, and the
+// most important thing in the block is shown, from the bottom of the list up.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum Mark {
+    Unexplored,
+    Wall,
+    Floor,
+    Item,
+    Monster,
+    Shop,
+    Stairs,
+    Player,
+}
+
+impl Mark {
+    const LEGEND: [Mark; 7] = [
+        Mark::Player,
+        Mark::Stairs,
+        Mark::Shop,
+        Mark::Monster,
+        Mark::Item,
+        Mark::Floor,
+        Mark::Wall,
+    ];
+
+    fn glyph(self) -> char {
+        match self {
+            Mark::Unexplored => ' ',
+            Mark::Wall => '#',
+            Mark::Floor => '.',
+            Mark::Item => '*',
+            Mark::Monster => 'M',
+            Mark::Shop => '$',
+            Mark::Stairs => '<',
+            Mark::Player => '@',
+        }
+    }
+
+    fn color(self) -> Color {
+        match self {
+            Mark::Unexplored => BLACK,
+            Mark::Wall => COLOR_DARK_WALL,
+            Mark::Floor => COLOR_DARK_GROUND,
+            Mark::Item => LIGHT_BLUE,
+            Mark::Monster => RED,
+            Mark::Shop => YELLOW,
+            Mark::Stairs => WHITE,
+            Mark::Player => LIGHT_GREEN,
+        }
+    }
+
+    fn description(self) -> &'static str {
+        match self {
+            Mark::Unexplored => "unexplored",
+            Mark::Wall => "wall",
+            Mark::Floor => "floor",
+            Mark::Item => "item",
+            Mark::Monster => "monster, last seen",
+            Mark::Shop => "shop",
+            Mark::Stairs => "stairs",
+            Mark::Player => "you",
+        }
+    }
+}
+
+// Monsters are shown on the overview where the player last saw them, so
+// their position is noted on every turn they're in view.
+pub fn remember_monsters(sight: &Sight, objects: &mut [GameObject]) {
+    for object in objects.iter_mut().filter(|o| o.ai.is_some() && o.fighter.is_some()) {
+        if sight.is_visible(object.x, object.y) {
+            object.last_seen = Some(object.pos());
+        }
+    }
+}
+
+// The whole explored level at once, shrunk down to fit the screen if it's
+// bigger, with a legend next to it.
+pub fn overview_screen(tcod: &mut Tcod, game: &Game, objects: &[GameObject]) {
+    let mut marks = vec![vec![Mark::Unexplored; MAP_HEIGHT as usize]; MAP_WIDTH as usize];
+    for x in 0..MAP_WIDTH {
+        for y in 0..MAP_HEIGHT {
+            let tile = &game.game_map[x as usize][y as usize];
+            if tile.explored {
+                marks[x as usize][y as usize] = if tile.block_sight { Mark::Wall } else { Mark::Floor };
+            }
+        }
+    }
+    for (id, object) in objects.iter().enumerate() {
+        let (mark, (x, y)) = if id == PLAYER {
+            (Mark::Player, object.pos())
+        } else if let Some(pos) = object.last_seen.filter(|_| object.ai.is_some()) {
+            (Mark::Monster, pos)
+        } else if !game.game_map[object.x as usize][object.y as usize].explored {
+            continue;
+        } else if object.name == "stairs" {
+            (Mark::Stairs, object.pos())
+        } else if object.shop.is_some() {
+            (Mark::Shop, object.pos())
+        } else if object.item.is_some() && (object.always_visible || tcod.sight.is_visible(object.x, object.y)) {
+            (Mark::Item, object.pos())
+        } else {
+            continue;
+        };
+        let cell = &mut marks[x as usize][y as usize];
+        *cell = (*cell).max(mark);
+    }
+
+    // each cell covers a square block of tiles, as small as still fits
+    let scale = ((MAP_WIDTH + LEGEND_X - 2) / (LEGEND_X - 1))
+        .max((MAP_HEIGHT + OVERVIEW_HEIGHT - 1) / OVERVIEW_HEIGHT)
+        .max(1);
+
+    let root = &mut tcod.root;
+    root.set_default_background(BLACK);
+    root.clear();
+    root.set_default_foreground(WHITE);
+    root.print_ex(
+        SCREEN_WIDTH / 2,
+        0,
+        BackgroundFlag::None,
+        TextAlignment::Center,
+        format!("Dungeon level {}", game.dungeon_level),
+    );
+
+    for cell_x in 0..(MAP_WIDTH + scale - 1) / scale {
+        for cell_y in 0..(MAP_HEIGHT + scale - 1) / scale {
+            let mut mark = Mark::Unexplored;
+            for x in cell_x * scale..((cell_x + 1) * scale).min(MAP_WIDTH) {
+                for y in cell_y * scale..((cell_y + 1) * scale).min(MAP_HEIGHT) {
+                    mark = mark.max(marks[x as usize][y as usize]);
+                }
+            }
+            root.set_default_foreground(mark.color());
+            root.put_char(cell_x, cell_y + 2, mark.glyph(), BackgroundFlag::None);
+        }
+    }
+
+    for (index, mark) in Mark::LEGEND.iter().enumerate() {
+        let y = index as i32 + 2;
+        root.set_default_foreground(mark.color());
+        root.put_char(LEGEND_X, y, mark.glyph(), BackgroundFlag::None);
+        root.set_default_foreground(LIGHT_GREY);
+        root.print_ex(LEGEND_X + 2, y, BackgroundFlag::None, TextAlignment::Left, mark.description());
+    }
+
+    root.set_default_foreground(LIGHT_GREY);
+    root.print_ex(
+        1,
+        SCREEN_HEIGHT - 1,
+        BackgroundFlag::None,
+        TextAlignment::Left,
+        "Press any key to continue",
+    );
+    root.flush();
+    root.wait_for_keypress(true);
+}
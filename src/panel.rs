@@ -1,3 +1,4 @@
+use std::collections::VecDeque;
 use serde::{Deserialize, Serialize};
 use tcod::colors::*;
 use tcod::console::*;
@@ -46,22 +47,69 @@ pub const MSG_X: i32 = BAR_WIDTH + 2;
 pub const MSG_WIDTH: i32 = SCREEN_HEIGHT - BAR_WIDTH - 2;
 pub const MSG_HEIGHT: usize = PANEL_HEIGHT as usize - 1;
 
+// the oldest messages are dropped past this many, to keep the save file small
+const MAX_MESSAGES: usize = 500;
+
+// a message, with the turn it was last seen on and how many times in a row
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Message {
+    pub text: String,
+    pub color: Color,
+    pub turn: u32,
+    pub count: u32,
+}
+
+impl Message {
+    // "orc attacks player for 2 hit points. (x3)"
+    pub fn display(&self) -> String {
+        if self.count > 1 {
+            format!("{} (x{})", self.text, self.count)
+        } else {
+            self.text.clone()
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct Messages {
-    messages: Vec<(String, Color)>
+    messages: VecDeque<Message>,
+    // every message ever added, including the ones that were dropped
+    total: usize,
+    // the turn new messages are tagged with
+    turn: u32,
 }
 
 impl Messages {
     pub fn new() -> Self {
-        Self { messages: vec![] }
+        Self { messages: VecDeque::new(), total: 0, turn: 0 }
     }
 
+    // the same message as the last one just bumps its count
     pub fn add<T: Into<String>>(&mut self, message: T, color: Color) {
-        self.messages.push((message.into(), color));
+        let text = message.into();
+        self.total += 1;
+        if let Some(last) = self.messages.back_mut() {
+            if last.text == text && last.color == color {
+                last.count += 1;
+                last.turn = self.turn;
+                return;
+            }
+        }
+        if self.messages.len() == MAX_MESSAGES {
+            self.messages.pop_front();
+        }
+        self.messages.push_back(Message { text, color, turn: self.turn, count: 1 });
     }
 
-    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &(String, Color)> {
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &Message> {
         self.messages.iter()
     }
-}
 
+    pub fn total(&self) -> usize {
+        self.total
+    }
+
+    pub fn set_turn(&mut self, turn: u32) {
+        self.turn = turn;
+    }
+}
@@ -0,0 +1,51 @@
+use std::collections::VecDeque;
+use crate::game::*;
+use crate::game_object::*;
+use crate::map::*;
+
+// Searches breadth first from the player through the explored tiles that can
+// be walked on, for the closest tile that passes the test.  Returns the first
+// step on the way there, or None if there's no such tile the player can reach.
+pub fn first_step<F>(game: &Game, objects: &[GameObject], is_goal: F) -> Option<(i32, i32)>
+where
+    F: Fn(i32, i32) -> bool,
+{
+    let start = objects[PLAYER].pos();
+    let mut came_from = vec![vec![None; MAP_HEIGHT as usize]; MAP_WIDTH as usize];
+    came_from[start.0 as usize][start.1 as usize] = Some(start);
+    let mut frontier = VecDeque::new();
+    frontier.push_back(start);
+
+    while let Some((x, y)) = frontier.pop_front() {
+        for dx in -1..=1 {
+            for dy in -1..=1 {
+                let (next_x, next_y) = (x + dx, y + dy);
+                let in_map = next_x >= 0 && next_y >= 0 && next_x < MAP_WIDTH && next_y < MAP_HEIGHT;
+                if !in_map
+                    || came_from[next_x as usize][next_y as usize].is_some()
+                    || is_blocked(next_x, next_y, &game.game_map, objects)
+                {
+                    continue;
+                }
+                came_from[next_x as usize][next_y as usize] = Some((x, y));
+
+                if is_goal(next_x, next_y) {
+                    // walk back to the tile right next to the player
+                    let mut step = (next_x, next_y);
+                    while let Some(previous) = came_from[step.0 as usize][step.1 as usize] {
+                        if previous == start {
+                            break;
+                        }
+                        step = previous;
+                    }
+                    return Some((step.0 - start.0, step.1 - start.1));
+                }
+                // the way on is only known through explored tiles
+                if game.game_map[next_x as usize][next_y as usize].explored {
+                    frontier.push_back((next_x, next_y));
+                }
+            }
+        }
+    }
+    None
+}
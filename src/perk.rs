@@ -0,0 +1,287 @@
+use serde::{Deserialize, Serialize};
+use tcod::colors::*;
+use crate::game::*;
+use crate::game_object::*;
+use crate::menu::{colored_menu, menu};
+
+const PERK_SCREEN_WIDTH: i32 = 70;
+const ABILITY_MENU_WIDTH: i32 = 40;
+
+// a regenerating player gets one hit point back every this many turns
+const REGENERATION_TURNS: u32 = 10;
+// the share of the maximum hit points a rally restores, in percent
+const RALLY_HEAL_PERCENT: i32 = 25;
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Perk {
+    Toughness,
+    Strength,
+    Agility,
+    Wisdom,
+    Attunement,
+    Precision,
+    Assassin,
+    Regeneration,
+    Pyromancy,
+    Whirlwind,
+    Rally
+}
+
+impl Perk {
+    // in the order they're shown on the perk screen, prerequisites first
+    pub const ALL: [Perk; 11] = [
+        Perk::Toughness,
+        Perk::Regeneration,
+        Perk::Rally,
+        Perk::Strength,
+        Perk::Whirlwind,
+        Perk::Agility,
+        Perk::Precision,
+        Perk::Assassin,
+        Perk::Wisdom,
+        Perk::Pyromancy,
+        Perk::Attunement,
+    ];
+
+    pub fn description(self) -> &'static str {
+        match self {
+            Perk::Toughness => "+20 maximum HP",
+            Perk::Strength => "+1 attack",
+            Perk::Agility => "+1 defense",
+            Perk::Wisdom => "+10 maximum mana",
+            Perk::Attunement => "+1 charge to every wand carried",
+            Perk::Precision => "+10% chance of a critical hit",
+            Perk::Assassin => "critical hits deal triple damage",
+            Perk::Regeneration => "slowly regain hit points",
+            Perk::Pyromancy => "+1 fireball radius",
+            Perk::Whirlwind => "ability: attack every adjacent enemy",
+            Perk::Rally => "ability: regain a quarter of your HP",
+        }
+    }
+
+    // the perk that has to be taken before this one
+    pub fn prerequisite(self) -> Option<Perk> {
+        match self {
+            Perk::Regeneration | Perk::Rally => Some(Perk::Toughness),
+            Perk::Whirlwind => Some(Perk::Strength),
+            Perk::Precision => Some(Perk::Agility),
+            Perk::Assassin => Some(Perk::Precision),
+            Perk::Pyromancy | Perk::Attunement => Some(Perk::Wisdom),
+            _ => None,
+        }
+    }
+
+    // active abilities have to be used, and need some turns to recover after
+    pub fn cooldown(self) -> Option<u32> {
+        match self {
+            Perk::Whirlwind => Some(20),
+            Perk::Rally => Some(100),
+            _ => None,
+        }
+    }
+
+    // the immediate effect of taking the perk, most perks are checked for when needed
+    fn apply(self, player: &mut GameObject, game: &mut Game) {
+        let fighter = player.fighter.as_mut().unwrap();
+        match self {
+            Perk::Toughness => {
+                fighter.base_max_hp += 20;
+                fighter.hp += 20;
+            }
+            Perk::Strength => fighter.base_power += 1,
+            Perk::Agility => fighter.base_defense += 1,
+            Perk::Wisdom => {
+                if let Some(caster) = player.caster.as_mut() {
+                    caster.max_mana += 10;
+                    caster.mana += 10;
+                }
+            }
+            Perk::Attunement => {
+                for charges in game.inventory.iter_mut().filter_map(|item| item.charges.as_mut()) {
+                    *charges += 1;
+                }
+                game.messages.add("Your wands hum with new power.", LIGHT_BLUE);
+            }
+            _ => {}
+        }
+    }
+}
+
+impl std::fmt::Display for Perk {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match *self {
+            Perk::Toughness => write!(f, "Toughness"),
+            Perk::Strength => write!(f, "Strength"),
+            Perk::Agility => write!(f, "Agility"),
+            Perk::Wisdom => write!(f, "Wisdom"),
+            Perk::Attunement => write!(f, "Attunement"),
+            Perk::Precision => write!(f, "Precision"),
+            Perk::Assassin => write!(f, "Assassin"),
+            Perk::Regeneration => write!(f, "Regeneration"),
+            Perk::Pyromancy => write!(f, "Pyromancy"),
+            Perk::Whirlwind => write!(f, "Whirlwind"),
+            Perk::Rally => write!(f, "Rally"),
+        }
+    }
+}
+
+// the perks taken, the points left to spend and the abilities still recovering
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Perks {
+    pub points: i32,
+    pub taken: Vec<Perk>,
+    pub cooldowns: Vec<(Perk, u32)>,
+}
+
+impl Perks {
+    pub fn new() -> Self {
+        Perks {
+            points: 0,
+            taken: vec![],
+            cooldowns: vec![],
+        }
+    }
+
+    pub fn can_take(&self, perk: Perk) -> bool {
+        !self.taken.contains(&perk) && perk.prerequisite().is_none_or(|p| self.taken.contains(&p))
+    }
+
+    pub fn cooldown_left(&self, perk: Perk) -> u32 {
+        self.cooldowns
+            .iter()
+            .find(|&&(p, _)| p == perk)
+            .map_or(0, |&(_, turns)| turns)
+    }
+}
+
+// Shows the whole tree and lets the player spend their points, until they
+// back out.
+pub fn perk_screen(tcod: &mut Tcod, game: &mut Game, objects: &mut [GameObject]) {
+    loop {
+        let perks = match objects[PLAYER].perks.as_ref() {
+            Some(perks) => perks.clone(),
+            None => return
+        };
+        let options: Vec<_> = Perk::ALL
+            .iter()
+            .map(|&perk| {
+                let indent = if perk.prerequisite().is_some() { "  " } else { "" };
+                let (status, color) = if perks.taken.contains(&perk) {
+                    ("taken", LIGHT_GREEN)
+                } else if perks.can_take(perk) {
+                    ("", WHITE)
+                } else {
+                    ("locked", DARK_GREY)
+                };
+                (format!("{}{} - {} {}", indent, perk, perk.description(), status), color)
+            })
+            .collect();
+        let header = format!(
+            "Perks, {} point(s) to spend.  Choose a perk, or any other key to leave.\n",
+            perks.points
+        );
+        let perk = match colored_menu(&header, &options, PERK_SCREEN_WIDTH, &mut tcod.root) {
+            Some(choice) => Perk::ALL[choice],
+            None => return
+        };
+
+        if perks.points <= 0 {
+            game.messages.add("You have no perk points to spend.", RED);
+            return;
+        } else if perks.taken.contains(&perk) {
+            game.messages.add(format!("You already have {}.", perk), WHITE);
+        } else if !perks.can_take(perk) {
+            game.messages.add(
+                format!("{} needs {} first.", perk, perk.prerequisite().unwrap()),
+                RED
+            );
+        } else {
+            let player = &mut objects[PLAYER];
+            let perks = player.perks.as_mut().unwrap();
+            perks.points -= 1;
+            perks.taken.push(perk);
+            perk.apply(player, game);
+            game.messages.add(format!("You gain the {} perk.", perk), YELLOW);
+        }
+    }
+}
+
+// Lets the player pick one of their active abilities and use it.  Returns true
+// if an ability was used.
+pub fn ability_menu(tcod: &mut Tcod, game: &mut Game, objects: &mut [GameObject]) -> bool {
+    let perks = objects[PLAYER].perks.clone().unwrap_or_else(Perks::new);
+    let abilities: Vec<_> = perks
+        .taken
+        .iter()
+        .cloned()
+        .filter(|perk| perk.cooldown().is_some())
+        .collect();
+    if abilities.is_empty() {
+        game.messages.add("You don't have any abilities.", WHITE);
+        return false;
+    }
+
+    let options: Vec<_> = abilities
+        .iter()
+        .map(|&perk| match perks.cooldown_left(perk) {
+            0 => perk.to_string(),
+            turns => format!("{} ({} turns)", perk, turns),
+        })
+        .collect();
+    let ability = match menu(
+        "Choose an ability to use, or any other key to cancel.\n",
+        &options,
+        ABILITY_MENU_WIDTH,
+        &mut tcod.root,
+    ) {
+        Some(choice) => abilities[choice],
+        None => return false
+    };
+    if perks.cooldown_left(ability) > 0 {
+        game.messages.add(format!("You need to recover before using {} again.", ability), RED);
+        return false;
+    }
+
+    match ability {
+        Perk::Whirlwind => whirlwind(game, objects),
+        Perk::Rally => {
+            let amount = objects[PLAYER].max_hp(game) * RALLY_HEAL_PERCENT / 100;
+            objects[PLAYER].heal(amount, game);
+            game.messages.add("You catch your breath and rally!", LIGHT_VIOLET);
+        }
+        _ => unreachable!()
+    }
+    let perks = objects[PLAYER].perks.as_mut().unwrap();
+    perks.cooldowns.push((ability, ability.cooldown().unwrap()));
+    true
+}
+
+fn whirlwind(game: &mut Game, objects: &mut [GameObject]) {
+    game.messages.add("You spin around, striking everything next to you!", LIGHT_RED);
+    let (x, y) = objects[PLAYER].pos();
+    let targets: Vec<_> = objects
+        .iter()
+        .enumerate()
+        .filter(|&(id, o)| {
+            id != PLAYER && o.fighter.is_some() && o.alive && (o.x - x).abs() <= 1 && (o.y - y).abs() <= 1
+        })
+        .map(|(id, _)| id)
+        .collect();
+    for target_id in targets {
+        let (player, target) = mut_two(PLAYER, target_id, objects);
+        player.attack(target, game);
+    }
+}
+
+// passive perks and recovering abilities, once per player turn
+pub fn tick(player: &mut GameObject, game: &mut Game) {
+    if player.has_perk(Perk::Regeneration) && game.turn.is_multiple_of(REGENERATION_TURNS) {
+        player.heal(1, game);
+    }
+    if let Some(perks) = player.perks.as_mut() {
+        for (_, turns) in perks.cooldowns.iter_mut() {
+            *turns -= 1;
+        }
+        perks.cooldowns.retain(|&(_, turns)| turns > 0);
+    }
+}
@@ -0,0 +1,92 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::fs::File;
+use std::io::Read;
+use serde::{Deserialize, Serialize};
+use crate::class::Class;
+
+const PROGRESSION_FILE: &str = "assets/progression.json";
+
+// How a class levels up.  The experience needed for a level comes from the
+// table while it lasts, and from the base and factor after that.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct LevelRules {
+    #[serde(default)]
+    pub xp_table: Vec<i32>,
+    pub xp_base: i32,
+    pub xp_factor: i32,
+    pub hp_per_level: i32,
+    pub mana_per_level: i32,
+    pub perk_points_per_level: i32,
+}
+
+impl LevelRules {
+    // the experience it takes to get from this level to the next one
+    pub fn xp_to_next(&self, level: i32) -> i32 {
+        self.xp_table
+            .get(level as usize - 1)
+            .cloned()
+            .unwrap_or(self.xp_base + level * self.xp_factor)
+    }
+}
+
+// the level-up rules for every class, tuned in the progression file
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Progression {
+    classes: HashMap<Class, LevelRules>,
+}
+
+// the progression file as it ships, built in for when the one on disk can't be used
+const SHIPPED_PROGRESSION: &str = include_str!("../assets/progression.json");
+
+impl Default for Progression {
+    fn default() -> Self {
+        let classes = serde_json::from_str(SHIPPED_PROGRESSION)
+            .expect("the shipped progression file is malformed");
+        Progression { classes }
+    }
+}
+
+impl Progression {
+    pub fn load() -> Result<Self, Box<dyn Error>> {
+        let mut json = String::new();
+        let mut file = File::open(PROGRESSION_FILE)
+            .map_err(|e| format!("can't open {}: {}", PROGRESSION_FILE, e))?;
+        file.read_to_string(&mut json)?;
+        let classes = serde_json::from_str(&json)
+            .map_err(|e| format!("{} is malformed: {}", PROGRESSION_FILE, e))?;
+        let progression = Progression { classes };
+        progression.validate()?;
+        Ok(progression)
+    }
+
+    // every class needs rules, and every level has to take some experience,
+    // or the player would level up forever
+    fn validate(&self) -> Result<(), Box<dyn Error>> {
+        for class in Class::ALL.iter() {
+            let rules = self
+                .classes
+                .get(class)
+                .ok_or_else(|| format!("{} has no rules for the {}", PROGRESSION_FILE, class))?;
+            if let Some(level) = rules.xp_table.iter().position(|&xp| xp <= 0) {
+                return Err(format!(
+                    "the {} xp_table in {} needs more than 0 experience for level {}",
+                    class, PROGRESSION_FILE, level + 2
+                ).into());
+            }
+            // past the table the thresholds only grow, if the factor isn't negative
+            let first_level = rules.xp_table.len() as i32 + 1;
+            if rules.xp_factor < 0 || rules.xp_to_next(first_level) <= 0 {
+                return Err(format!(
+                    "the {} xp_base and xp_factor in {} have to give more than 0 experience per level",
+                    class, PROGRESSION_FILE
+                ).into());
+            }
+        }
+        Ok(())
+    }
+
+    pub fn rules(&self, class: Class) -> &LevelRules {
+        &self.classes[&class]
+    }
+}
@@ -0,0 +1,130 @@
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use tcod::colors::*;
+use crate::game::*;
+use crate::game_object::*;
+use crate::encumbrance::can_carry;
+use crate::map::{new_item, random_item};
+use crate::menu::*;
+
+const SHOP_MENU_WIDTH: i32 = 50;
+const MIN_STOCK: i32 = 5;
+const MAX_STOCK: i32 = 8;
+
+// the shopkeeper can't keep track of more wares than fit in a menu
+const MAX_SHOP_ITEMS: usize = 26;
+
+// the wares a shopkeeper has for sale
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Shop {
+    pub stock: Vec<GameObject>,
+}
+
+pub fn new_shopkeeper(x: i32, y: i32, level: u32) -> GameObject {
+    let mut shopkeeper = GameObject::new(x, y, '@', "shopkeeper", YELLOW, true);
+    let num_items = rand::thread_rng().gen_range(MIN_STOCK, MAX_STOCK + 1);
+    let stock = (0..num_items)
+        .map(|_| new_item(random_item(level), 0, 0, level))
+        .filter(|item| item.item != Some(Item::Gold))
+        .collect();
+    shopkeeper.shop = Some(Shop { stock });
+    shopkeeper
+}
+
+// bumping into a shopkeeper opens the shop
+pub fn visit_shop(shop_id: usize, tcod: &mut Tcod, game: &mut Game, objects: &mut [GameObject]) {
+    loop {
+        let header = format!(
+            "\"Welcome, stranger! Care to trade?\"\nYou have {} gold.\n",
+            game.gold
+        );
+        match menu(&header, &["Buy", "Sell"], SHOP_MENU_WIDTH, &mut tcod.root) {
+            Some(0) => buy(shop_id, tcod, game, objects),
+            Some(1) => sell(shop_id, tcod, game, objects),
+            _ => break,
+        }
+    }
+}
+
+fn buy(shop_id: usize, tcod: &mut Tcod, game: &mut Game, objects: &mut [GameObject]) {
+    let (shop, player) = mut_two(shop_id, PLAYER, objects);
+    let shop = shop.shop.as_mut().unwrap();
+    if shop.stock.is_empty() {
+        game.messages.add("\"Sorry, I'm all sold out.\"", YELLOW);
+        return;
+    }
+
+    let options: Vec<_> = shop
+        .stock
+        .iter()
+        .map(|item| {
+            let color = item.equipment.map_or(WHITE, |e| e.rarity.color());
+            (format!("{} - {} gold", item.display_name(game), item.price()), color)
+        })
+        .collect();
+    let choice = colored_menu(
+        "Choose an item to buy, or any other key to cancel.\n",
+        &options,
+        SHOP_MENU_WIDTH,
+        &mut tcod.root,
+    );
+
+    if let Some(stock_id) = choice {
+        let price = shop.stock[stock_id].price();
+        let name = shop.stock[stock_id].amount_name(game);
+        if game.gold < price {
+            game.messages.add(format!("You can't afford {}.", name), RED);
+        } else if !has_room_for(&shop.stock[stock_id], game) {
+            game.messages.add("Your inventory is full.", RED);
+        } else if !can_carry(&shop.stock[stock_id], game, player) {
+            game.messages.add(format!("You can't carry {}.", name), RED);
+        } else {
+            let item = shop.stock.remove(stock_id);
+            game.gold -= price;
+            game.messages.add(format!("You bought {} for {} gold.", name, price), GOLD);
+            add_to_inventory(item, game);
+        }
+    }
+}
+
+fn sell(shop_id: usize, tcod: &mut Tcod, game: &mut Game, objects: &mut [GameObject]) {
+    let inventory_id = inventory_menu(
+        game,
+        &objects[PLAYER],
+        "Choose an item to sell, or any other key to cancel.\n",
+        &mut tcod.root,
+    );
+    let inventory_id = match inventory_id {
+        Some(inventory_id) => inventory_id,
+        None => return
+    };
+
+    let shop = objects[shop_id].shop.as_mut().unwrap();
+    if shop.stock.len() >= MAX_SHOP_ITEMS {
+        game.messages.add("\"I have no room for any more wares.\"", YELLOW);
+        return;
+    }
+    if game.inventory[inventory_id].equipment.is_some()
+        && !game.inventory[inventory_id].dequip(&mut game.messages)
+    {
+        return;
+    }
+
+    // shopkeepers pay half of what they ask, for one item at a time
+    let mut item = if game.inventory[inventory_id].count > 1 {
+        game.inventory[inventory_id].count -= 1;
+        let mut single = game.inventory[inventory_id].clone();
+        single.count = 1;
+        single
+    } else {
+        game.inventory.remove(inventory_id)
+    };
+    let price = item.price() / 2;
+    item.set_pos(0, 0);
+    game.gold += price;
+    game.messages.add(
+        format!("You sold {} for {} gold.", item.amount_name(game), price),
+        GOLD,
+    );
+    shop.stock.push(item);
+}
@@ -0,0 +1,110 @@
+use serde::{Deserialize, Serialize};
+use tcod::colors::*;
+use crate::game::*;
+use crate::game_object::*;
+use crate::menu::menu;
+
+const CAST_MENU_WIDTH: i32 = 40;
+
+// the player gets one point of mana back every this many turns
+const MANA_REGEN_TURNS: u32 = 5;
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Spell {
+    Lightning,
+    Confuse,
+    Fireball
+}
+
+impl Spell {
+    // the mana it takes to cast
+    pub fn cost(self) -> i32 {
+        match self {
+            Spell::Lightning => 5,
+            Spell::Confuse => 8,
+            Spell::Fireball => 12,
+        }
+    }
+}
+
+impl std::fmt::Display for Spell {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match *self {
+            Spell::Lightning => write!(f, "lightning bolt"),
+            Spell::Confuse => write!(f, "confusion"),
+            Spell::Fireball => write!(f, "fireball"),
+        }
+    }
+}
+
+// a mana pool and the spells learned so far
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Caster {
+    pub mana: i32,
+    pub max_mana: i32,
+    pub spells: Vec<Spell>,
+}
+
+impl Caster {
+    pub fn new(max_mana: i32, spells: Vec<Spell>) -> Self {
+        Caster {
+            mana: max_mana,
+            max_mana,
+            spells,
+        }
+    }
+}
+
+pub fn regenerate_mana(player: &mut GameObject, turn: u32) {
+    if let Some(caster) = player.caster.as_mut() {
+        if turn.is_multiple_of(MANA_REGEN_TURNS) && caster.mana < caster.max_mana {
+            caster.mana += 1;
+        }
+    }
+}
+
+// Lets the player pick a spell from their spellbook and cast it.  Returns true
+// if a spell was cast.
+pub fn cast_menu(tcod: &mut Tcod, game: &mut Game, objects: &mut [GameObject]) -> bool {
+    let caster = match objects[PLAYER].caster.as_ref() {
+        Some(caster) if !caster.spells.is_empty() => caster.clone(),
+        _ => {
+            game.messages.add("You don't know any spells.", WHITE);
+            return false;
+        }
+    };
+
+    let options: Vec<_> = caster
+        .spells
+        .iter()
+        .map(|spell| format!("{} ({} MP)", spell, spell.cost()))
+        .collect();
+    let header = format!(
+        "Choose a spell to cast, or any other key to cancel.\nMana: {}/{}\n",
+        caster.mana, caster.max_mana
+    );
+    let spell = match menu(&header, &options, CAST_MENU_WIDTH, &mut tcod.root) {
+        Some(choice) => caster.spells[choice],
+        None => return false
+    };
+    if caster.mana < spell.cost() {
+        game.messages.add(format!("You don't have enough mana to cast {}.", spell), RED);
+        return false;
+    }
+
+    let on_cast = match spell {
+        Spell::Lightning => lightning_bolt,
+        Spell::Confuse => confuse,
+        Spell::Fireball => fireball,
+    };
+    match on_cast(tcod, game, objects) {
+        UseResult::Cancelled => {
+            game.messages.add("Cancelled", WHITE);
+            false
+        }
+        _ => {
+            objects[PLAYER].caster.as_mut().unwrap().mana -= spell.cost();
+            true
+        }
+    }
+}
@@ -0,0 +1,243 @@
+use tcod::colors::*;
+use tcod::console::*;
+use tcod::input::{self, Event, KeyCode::*};
+use crate::game::*;
+use crate::keymap::Action;
+use crate::game_object::*;
+use crate::map::*;
+
+// the tint of the tiles within range of whatever is being targeted
+const COLOR_IN_RANGE: Color = Color { r: 60, g: 60, b: 110 };
+const RANGE_TINT: f32 = 0.5;
+
+// the reticle shown while the player looks around or picks a target
+#[derive(Clone, Copy, Debug)]
+pub struct Cursor {
+    pub x: i32,
+    pub y: i32,
+    pub max_range: Option<f32>,
+}
+
+impl Cursor {
+    fn in_range(&self, x: i32, y: i32, player: &GameObject) -> bool {
+        self.max_range.is_none_or(|range| player.distance(x, y) <= range)
+    }
+}
+
+// Lets the player pick a visible tile within range, with the numpad or arrow
+// keys, Tab to jump between monsters and Enter to confirm, or with the mouse.
+pub fn target_tile(
+    tcod: &mut Tcod,
+    game: &mut Game,
+    objects: &[GameObject],
+    max_range: Option<f32>
+) -> Option<(i32, i32)> {
+    let mut picker = Picker::new(tcod, objects, max_range);
+    loop {
+        let (x, y) = pick_tile(&mut picker, tcod, game, objects)?;
+        if !tcod.sight.is_visible(x, y) {
+            game.messages.add("You can't see there.", WHITE);
+        } else if !picker.cursor.in_range(x, y, &objects[PLAYER]) {
+            game.messages.add("That's out of range.", WHITE);
+        } else {
+            return Some((x, y));
+        }
+    }
+}
+
+// look around the map without doing anything
+pub fn look(tcod: &mut Tcod, game: &mut Game, objects: &[GameObject]) {
+    let mut picker = Picker::new(tcod, objects, None);
+    while let Some((x, y)) = pick_tile(&mut picker, tcod, game, objects) {
+        let description = describe(x, y, game, objects, tcod);
+        game.messages.add(format!("You see: {}", description), LIGHT_GREY);
+    }
+}
+
+// where the cursor is, kept between choices so it stays put after each one
+struct Picker {
+    cursor: Cursor,
+    monsters: Vec<usize>,
+    next_monster: usize,
+    last_mouse: (isize, isize),
+}
+
+impl Picker {
+    // starts on the closest monster, or on the player if there's none in sight
+    fn new(tcod: &Tcod, objects: &[GameObject], max_range: Option<f32>) -> Self {
+        let monsters = visible_monsters(tcod, objects);
+        let (x, y) = monsters
+            .first()
+            .map_or(objects[PLAYER].pos(), |&id| objects[id].pos());
+        Picker {
+            cursor: Cursor { x, y, max_range },
+            monsters,
+            next_monster: 1,
+            last_mouse: (tcod.mouse.cx, tcod.mouse.cy),
+        }
+    }
+}
+
+// moves the cursor until a tile is chosen, or returns None if cancelled
+fn pick_tile(
+    picker: &mut Picker,
+    tcod: &mut Tcod,
+    game: &mut Game,
+    objects: &[GameObject]
+) -> Option<(i32, i32)> {
+    let Picker { cursor, monsters, next_monster, last_mouse } = picker;
+
+    let choice = loop {
+        tcod.cursor = Some(*cursor);
+        tcod.con.clear();
+        render_all(tcod, game, objects, false);
+        tcod.root.flush();
+
+        // a click only counts on the frame it happens, like in the main loop
+        tcod.mouse.lbutton_pressed = false;
+        tcod.mouse.rbutton_pressed = false;
+        let event = input::check_for_event(input::KEY_PRESS | input::MOUSE).map(|e| e.1);
+        match event {
+            Some(Event::Mouse(m)) => {
+                tcod.mouse = m;
+                tcod.key = Default::default();
+            }
+            Some(Event::Key(k)) => tcod.key = k,
+            None => tcod.key = Default::default()
+        }
+
+        // the mouse takes over the cursor whenever it moves over the map
+        let mouse = tcod.camera.to_map(tcod.mouse.cx as i32, tcod.mouse.cy as i32);
+        if (tcod.mouse.cx, tcod.mouse.cy) != *last_mouse {
+            *last_mouse = (tcod.mouse.cx, tcod.mouse.cy);
+            if let Some((x, y)) = mouse {
+                cursor.x = x;
+                cursor.y = y;
+            }
+        }
+        if tcod.mouse.lbutton_pressed {
+            if let Some(pos) = mouse {
+                break Some(pos);
+            }
+        }
+        if tcod.mouse.rbutton_pressed {
+            break None;
+        }
+
+        let (dx, dy) = match tcod.key.code {
+            Up | NumPad8 => (0, -1),
+            Down | NumPad2 => (0, 1),
+            Left | NumPad4 => (-1, 0),
+            Right | NumPad6 => (1, 0),
+            Home | NumPad7 => (-1, -1),
+            PageUp | NumPad9 => (1, -1),
+            End | NumPad1 => (-1, 1),
+            PageDown | NumPad3 => (1, 1),
+            Tab if !monsters.is_empty() => {
+                let monster = &objects[monsters[*next_monster % monsters.len()]];
+                cursor.x = monster.x;
+                cursor.y = monster.y;
+                *next_monster += 1;
+                (0, 0)
+            }
+            Enter | NumPadEnter => break Some((cursor.x, cursor.y)),
+            Escape => break None,
+            // the player's own movement keys move the cursor too
+            _ => tcod.keymap.action(tcod.key).and_then(Action::direction).unwrap_or((0, 0)),
+        };
+        cursor.x = (cursor.x + dx).clamp(0, MAP_WIDTH - 1);
+        cursor.y = (cursor.y + dy).clamp(0, MAP_HEIGHT - 1);
+    };
+
+    tcod.cursor = None;
+    tcod.key = Default::default();
+    tcod.mouse.lbutton_pressed = false;
+    tcod.mouse.rbutton_pressed = false;
+    choice
+}
+
+// the monsters the player can see, the closest first
+fn visible_monsters(tcod: &Tcod, objects: &[GameObject]) -> Vec<usize> {
+    let mut monsters: Vec<_> = objects
+        .iter()
+        .enumerate()
+        .filter(|&(id, o)| {
+            id != PLAYER && o.fighter.is_some() && o.ai.is_some() && tcod.sight.is_visible(o.x, o.y)
+        })
+        .map(|(id, _)| id)
+        .collect();
+    let player = &objects[PLAYER];
+    monsters.sort_by(|&a, &b| {
+        player
+            .distance_to(&objects[a])
+            .partial_cmp(&player.distance_to(&objects[b]))
+            .unwrap()
+    });
+    monsters
+}
+
+// tints the tiles in range and highlights the one under the cursor, green if
+// it can be chosen and red if it can't
+pub fn draw_cursor(tcod: &mut Tcod, objects: &[GameObject]) {
+    let cursor = match tcod.cursor {
+        Some(cursor) => cursor,
+        None => return
+    };
+    let player = &objects[PLAYER];
+    if cursor.max_range.is_some() {
+        for y in 0..MAP_HEIGHT {
+            for x in 0..MAP_WIDTH {
+                if tcod.sight.is_visible(x, y) && cursor.in_range(x, y, player) {
+                    let background = tcod.con.get_char_background(x, y);
+                    let tinted = lerp(background, COLOR_IN_RANGE, RANGE_TINT);
+                    tcod.con.set_char_background(x, y, tinted, BackgroundFlag::Set);
+                }
+            }
+        }
+    }
+    let valid = tcod.sight.is_visible(cursor.x, cursor.y) && cursor.in_range(cursor.x, cursor.y, player);
+    let color = if valid { DARK_GREEN } else { DARK_RED };
+    tcod.con.set_char_background(cursor.x, cursor.y, color, BackgroundFlag::Set);
+}
+
+// what the player knows about a tile, for the panel and the look command
+pub fn describe(x: i32, y: i32, game: &Game, objects: &[GameObject], tcod: &Tcod) -> String {
+    if x < 0 || y < 0 || x >= MAP_WIDTH || y >= MAP_HEIGHT {
+        return String::new();
+    }
+    let tile = &game.game_map[x as usize][y as usize];
+    if !tile.explored {
+        return "unexplored".into();
+    }
+    if !tcod.sight.is_visible(x, y) {
+        let terrain = if tile.block_sight { "wall" } else { "floor" };
+        return format!("{} (remembered)", terrain);
+    }
+
+    let names: Vec<_> = objects
+        .iter()
+        .filter(|o| o.pos() == (x, y))
+        .map(|o| match o.fighter {
+            Some(fighter) if o.alive && o.ai.is_some() => {
+                format!("{} ({})", o.display_name(game), health(fighter.hp, o.max_hp(game)))
+            }
+            _ => o.display_name(game),
+        })
+        .collect();
+    if !names.is_empty() {
+        names.join(", ")
+    } else if tile.block_sight {
+        "wall".into()
+    } else {
+        "floor".into()
+    }
+}
+
+fn health(hp: i32, max_hp: i32) -> &'static str {
+    match hp * 100 / max_hp.max(1) {
+        100..=i32::MAX => "unhurt",
+        60..=99 => "wounded",
+        25..=59 => "badly wounded",
+        _ => "nearly dead",
+    }
+}